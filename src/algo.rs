@@ -1,3 +1,213 @@
+pub(crate) mod astar;
+pub(crate) mod backtrack;
+pub(crate) mod binary;
+pub(crate) mod braid;
+pub(crate) mod diameter;
 pub(crate) mod dist;
+pub(crate) mod division;
+pub(crate) mod eller;
+pub(crate) mod growing;
 pub(crate) mod hk;
+pub(crate) mod kruskal;
 pub(crate) mod path;
+pub(crate) mod prim;
+pub(crate) mod rooms;
+pub(crate) mod sidewinder;
+pub(crate) mod ust;
+
+use crate::graphs::{Graph, Node};
+use crate::render::state::graph;
+use crate::render::{RenderGraph, RenderState};
+use image::Rgba;
+use webp_animation::{Encoder, WebPData};
+
+/// Background of the node currently being visited in animations
+const SEL_NODE: Rgba<u8> = crate::color::LIGHT_GREEN;
+/// Background of nodes that are part of the current walk in animations
+const IN_PATH: Rgba<u8> = crate::color::GREEN;
+
+/// Nodes of each connected component (ignoring links), in id order
+pub(crate) fn components<G: Graph>(graph: &G) -> Vec<Vec<usize>> {
+    let mut label = vec![None; graph.len()];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for id in graph.node_ids() {
+        if label[id].is_some() {
+            continue;
+        }
+        label[id] = Some(groups.len());
+        let mut stack = vec![id];
+        while let Some(node) = stack.pop() {
+            for n in graph.node(node).neighbors() {
+                if label[*n].is_none() {
+                    label[*n] = Some(groups.len());
+                    stack.push(*n);
+                }
+            }
+        }
+        groups.push(Vec::new());
+    }
+    for id in graph.node_ids() {
+        if let Some(l) = label[id] {
+            groups[l].push(id);
+        }
+    }
+    groups
+}
+
+/// Weight of every edge: copied from `weights`, or random when there are none
+///
+/// `weights` must have been created for a graph with the same nodes: every edge between two
+/// neighbors of `graph` needs a value.
+pub(crate) fn edge_weights<G, R>(
+    graph: &G,
+    weights: Option<&crate::graphs::weight::Uniform>,
+    rng: &mut R,
+) -> Result<crate::edges::Undirected<usize>, crate::Error>
+where
+    G: Graph,
+    R: rand::Rng + ?Sized,
+{
+    if let Some(weights) = weights {
+        let edges = weights.edges();
+        if edges.len() != graph.len() {
+            return Err(crate::Error::MismatchedListSize(edges.len(), graph.len()));
+        }
+        for node in graph.nodes() {
+            for (side, n) in node.all_neighbors().iter().enumerate() {
+                if n.is_some() && edges.edge_value(node.id(), side).is_err() {
+                    return Err(crate::Error::InvalidEdge(node.id(), side));
+                }
+            }
+        }
+        return Ok(edges.clone());
+    }
+    let mut edges = crate::edges::Undirected::new(graph, 0, 0);
+    let conns: Vec<(usize, usize)> = edges.iter().map(|e| (e.a().id(), e.a().side())).collect();
+    for (id, side) in conns {
+        let _ = edges.set_edge_value(id, side, rng.gen::<u32>() as usize);
+    }
+    Ok(edges)
+}
+
+/// Render the current state as the next frame of an animation
+pub(crate) fn frame<G>(
+    graph: &graph::State<G>,
+    encoder: &mut Encoder,
+    timestamp: &mut i32,
+    opts: &crate::render::opts::AnimOpts,
+) -> Result<(), crate::Error>
+where
+    G: Graph + RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    let image = graph.render();
+    encoder.add_frame(&image, *timestamp)?;
+    *timestamp += opts.frame_time();
+    Ok(())
+}
+
+/// Lets a generator share one implementation between its plain and animated versions.
+///
+/// Both versions consume the rng the same way, so a seed produces the same maze either way.
+pub(crate) trait Canvas {
+    type Graph: Graph;
+
+    fn graph(&self) -> &Self::Graph;
+    fn graph_mut(&mut self) -> &mut Self::Graph;
+
+    /// Set the background color of a node (animations only)
+    fn set_bg(&mut self, _id: usize, _color: Option<Rgba<u8>>) {}
+
+    /// Add a frame showing the current state (animations only)
+    fn frame(&mut self) -> Result<(), crate::Error> {
+        Ok(())
+    }
+}
+
+/// Generate a maze without rendering anything
+pub(crate) struct Plain<'a, G>(pub(crate) &'a mut G);
+
+impl<G: Graph> Canvas for Plain<'_, G> {
+    type Graph = G;
+
+    fn graph(&self) -> &G {
+        self.0
+    }
+
+    fn graph_mut(&mut self) -> &mut G {
+        self.0
+    }
+}
+
+/// Generate a maze while recording each step as a frame of an animated WebP
+pub(crate) struct Animation<'a, 'b, 'c, 'e, 'g, 'o, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    state: &'a mut graph::State<'b, 'c, 'e, 'g, 'o, G>,
+    opts: &'a crate::render::opts::AnimOpts,
+    encoder: Encoder,
+    timestamp: i32,
+}
+
+impl<'a, 'b, 'c, 'e, 'g, 'o, G> Animation<'a, 'b, 'c, 'e, 'g, 'o, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    pub(crate) fn new(
+        state: &'a mut graph::State<'b, 'c, 'e, 'g, 'o, G>,
+        opts: &'a crate::render::opts::AnimOpts,
+    ) -> Result<Self, crate::Error> {
+        let encoder = Encoder::new_with_options(state.size(), opts.encoder_options())?;
+        Ok(Self {
+            state,
+            opts,
+            encoder,
+            timestamp: 0,
+        })
+    }
+
+    /// Add a last frame and encode the animation
+    pub(crate) fn finish(mut self) -> Result<WebPData, crate::Error> {
+        self.frame()?;
+        self.encode()
+    }
+
+    /// Encode the animation as it is, without adding a last frame
+    pub(crate) fn encode(self) -> Result<WebPData, crate::Error> {
+        self.encoder
+            .finalize(self.timestamp + self.opts.repeat_delay())
+            .map_err(crate::Error::from)
+    }
+}
+
+impl<G> Canvas for Animation<'_, '_, '_, '_, '_, '_, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    type Graph = G;
+
+    fn graph(&self) -> &G {
+        self.state.graph()
+    }
+
+    fn graph_mut(&mut self) -> &mut G {
+        self.state.graph_mut()
+    }
+
+    fn set_bg(&mut self, id: usize, color: Option<Rgba<u8>>) {
+        self.state.set_bg(id, color);
+    }
+
+    fn frame(&mut self) -> Result<(), crate::Error> {
+        frame(
+            self.state,
+            &mut self.encoder,
+            &mut self.timestamp,
+            self.opts,
+        )
+    }
+}
@@ -0,0 +1,289 @@
+use crate::graphs::weight::Weights;
+use crate::graphs::{Graph, Node};
+use crate::{Delta, Hex, HexOrientation, Levels, Orth, Polar, RectCell, SparseGraph, Upsilon};
+use crate::{Voronoi, Weave, Wrap};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Estimate of the number of steps between two nodes, used to guide [`astar`].
+///
+/// The estimate must never be more than the real number of steps.  The default estimate of 0 is
+/// always safe, but turns the search into a plain breadth first search.
+pub trait Heuristic: Graph {
+    fn heuristic(&self, _from: usize, _to: usize) -> usize {
+        0
+    }
+}
+
+/// Distance along one axis, taking the shorter way around when the axis wraps
+fn axis(a: usize, b: usize, len: usize, wraps: bool) -> usize {
+    let d = a.abs_diff(b);
+    match wraps {
+        true => d.min(len - d),
+        false => d,
+    }
+}
+
+/// Manhattan distance
+impl Heuristic for Orth<RectCell> {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        let (height, width) = (self.height(), self.width());
+        let wrap = self.wrap();
+        let (r1, c1, r2, c2) = (from / width, from % width, to / width, to % width);
+        match wrap {
+            // crossing the seam flips the row, so take the shorter of the two ways around
+            Wrap::Mobius => (r1.abs_diff(r2) + c1.abs_diff(c2))
+                .min(r1.abs_diff(height - 1 - r2) + width - c1.abs_diff(c2)),
+            _ => axis(r1, r2, height, wrap.vertical()) + axis(c1, c2, width, wrap.horizontal()),
+        }
+    }
+}
+
+/// Manhattan distance, as every step moves one row or one column
+impl Heuristic for Delta {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        let (a, b) = (self.node(from), self.node(to));
+        a.row().abs_diff(b.row()) as usize + a.col().abs_diff(b.col()) as usize
+    }
+}
+
+/// Hex distance, using cube coordinates
+impl Heuristic for Hex {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        let cube = |id: usize| {
+            let node = self.node(id);
+            let (row, col) = (node.row() as isize, node.col() as isize);
+            match self.orientation() {
+                HexOrientation::PointyTop => (col - (row - (row & 1)) / 2, row),
+                HexOrientation::FlatTop => (col, row - (col - (col & 1)) / 2),
+            }
+        };
+        let ((aq, ar), (bq, br)) = (cube(from), cube(to));
+        let (dq, dr) = (aq - bq, ar - br);
+        dq.abs().max(dr.abs()).max((dq + dr).abs()) as usize
+    }
+}
+
+/// Chebyshev distance, as octagons can move diagonally
+impl Heuristic for Upsilon {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        let (a, b) = (self.node(from), self.node(to));
+        a.row().abs_diff(b.row()).max(a.col().abs_diff(b.col())) as usize
+    }
+}
+
+/// Manhattan distance halved, as a tunnel moves two cells in one step
+impl Heuristic for Weave {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        let (a, b) = (self.node(from), self.node(to));
+        (a.row().abs_diff(b.row()) as usize + a.col().abs_diff(b.col()) as usize).div_ceil(2)
+    }
+}
+
+/// Manhattan distance, counting each level as one step
+impl Heuristic for Levels {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        let (a, b) = (self.node(from), self.node(to));
+        a.level().abs_diff(b.level()) as usize
+            + a.row().abs_diff(b.row()) as usize
+            + a.col().abs_diff(b.col()) as usize
+    }
+}
+
+/// Number of rings apart, as every step moves at most one ring in or out
+impl Heuristic for Polar {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        self.node(from).row().abs_diff(self.node(to).row()) as usize
+    }
+}
+
+/// Straight line distance divided by the longest edge, as no step can cover more than that
+impl Heuristic for SparseGraph {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        match self.longest_edge() > 0.0 {
+            true => (self.distance(from, to) / self.longest_edge()).floor() as usize,
+            false => 0,
+        }
+    }
+}
+
+/// Same as the underlying [`SparseGraph`]
+impl Heuristic for Voronoi {
+    fn heuristic(&self, from: usize, to: usize) -> usize {
+        self.sparse().heuristic(from, to)
+    }
+}
+
+/// A* search for the shortest path between `start` and `end` along links.
+///
+/// Without `weights` every step costs 1.  Returns the path along with the number of nodes that
+/// were expanded, which is at most the number of nodes a breadth first search would visit.
+pub fn astar<G: Heuristic>(
+    graph: &G,
+    start: usize,
+    end: usize,
+    weights: Option<&dyn Weights>,
+) -> Result<(crate::Path, usize), crate::Error> {
+    for id in [start, end] {
+        if id >= graph.len() {
+            return Err(crate::Error::InvalidId(id, graph.len()));
+        }
+    }
+    // scale the estimate so it stays below the real cost
+    let scale = weights.map_or(1, |w| w.min_weight());
+    let estimate = |id: usize| graph.heuristic(id, end) * scale;
+
+    let mut cost: Vec<Option<usize>> = vec![None; graph.len()];
+    let mut prev: Vec<Option<usize>> = vec![None; graph.len()];
+    let mut heap = BinaryHeap::new();
+    let mut expanded = 0;
+    cost[start] = Some(0);
+    // break ties towards nodes closer to the end
+    heap.push(Reverse((estimate(start), Reverse(0), start)));
+
+    while let Some(Reverse((_, Reverse(g), cell))) = heap.pop() {
+        if cost[cell].is_some_and(|c| c < g) {
+            continue;
+        }
+        expanded += 1;
+        if cell == end {
+            break;
+        }
+        let node = graph.node(cell);
+        for link in node.links() {
+            let step = match weights {
+                Some(w) => {
+                    let side = node
+                        .neighbor_id(*link)
+                        .ok_or(crate::Error::InvalidEdge(cell, *link))?;
+                    w.weight(cell, side)?
+                }
+                None => 1,
+            };
+            let next = g.saturating_add(step);
+            let better = match cost[*link] {
+                Some(c) => next < c,
+                None => true,
+            };
+            if better {
+                cost[*link] = Some(next);
+                prev[*link] = Some(cell);
+                heap.push(Reverse((
+                    next.saturating_add(estimate(*link)),
+                    Reverse(next),
+                    *link,
+                )));
+            }
+        }
+    }
+
+    if cost[end].is_none() {
+        return Err(crate::Error::NoPathAvailable(end));
+    }
+    let mut steps = vec![end];
+    while let Some(p) = prev[*steps.last().unwrap()] {
+        steps.push(p);
+    }
+    let mut path = crate::Path::with_capacity(graph, steps.len() - 1);
+    for id in steps.into_iter().rev() {
+        path.add(id)?;
+    }
+    Ok((path, expanded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::braid::braid;
+    use crate::algo::hk::huntkill;
+    use crate::graphs::weight::Uniform;
+    use crate::Dist;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    /// A* finds paths as short as a breadth first search
+    fn check<G: Heuristic>(graph: &G, start: usize, end: usize) -> usize {
+        let (path, expanded) = astar(graph, start, end, None).unwrap();
+        let dist = Dist::simple(graph, start);
+        assert_eq!(Some(path.path.len() - 1), dist.dist(end));
+        assert_eq!(path.path.first(), Some(&start));
+        assert_eq!(path.path.last(), Some(&end));
+        assert!(path
+            .path
+            .windows(2)
+            .all(|w| graph.node(w[0]).linked_to(w[1])));
+        expanded
+    }
+
+    #[test]
+    fn astar_paths() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+
+        let mut rect: Orth<RectCell> = Orth::new(12, 12);
+        huntkill(&mut rect, rng).unwrap();
+        braid(&mut rect, 0.5, rng).unwrap();
+        assert!(check(&rect, 0, rect.len() - 1) <= rect.len());
+
+        for mut hex in [Hex::pointy(10, 10), Hex::flat(10, 10)] {
+            huntkill(&mut hex, rng).unwrap();
+            braid(&mut hex, 0.5, rng).unwrap();
+            check(&hex, 0, hex.len() - 1);
+            check(&hex, hex.len() - 1, 3);
+        }
+
+        let mut delta = Delta::new(8, 12);
+        huntkill(&mut delta, rng).unwrap();
+        braid(&mut delta, 0.5, rng).unwrap();
+        check(&delta, 0, delta.len() - 1);
+
+        let mut upsilon = Upsilon::new(10, 10);
+        huntkill(&mut upsilon, rng).unwrap();
+        braid(&mut upsilon, 0.5, rng).unwrap();
+        check(&upsilon, 0, upsilon.len() - 1);
+
+        let mut polar = Polar::new(6);
+        huntkill(&mut polar, rng).unwrap();
+        braid(&mut polar, 0.5, rng).unwrap();
+        check(&polar, 0, polar.len() - 1);
+        check(&polar, polar.len() - 1, 5);
+
+        // the seam joins row r to row height - 1 - r, so the shortest way may cross it
+        let mut mobius: Orth<RectCell> = Orth::mobius(8, 8);
+        huntkill(&mut mobius, rng).unwrap();
+        braid(&mut mobius, 1.0, rng).unwrap();
+        for end in [7, 56, 63] {
+            check(&mobius, 0, end);
+        }
+        let grid: Orth<RectCell> = Orth::new_wrapped(8, 8, Wrap::Mobius);
+        assert_eq!(grid.heuristic(0, 63), 1);
+
+        let mut voronoi = Voronoi::random(10, 10, 1.0, rng);
+        huntkill(&mut voronoi, rng).unwrap();
+        braid(&mut voronoi, 0.5, rng).unwrap();
+        check(&voronoi, 0, voronoi.len() - 1);
+        check(voronoi.sparse(), voronoi.len() - 1, 0);
+    }
+
+    #[test]
+    fn astar_expanded() {
+        crate::logger(log::LevelFilter::Warn);
+        // in an open grid only the nodes along the path are expanded
+        let graph: Orth<RectCell> = Orth::new_linked(16, 16);
+        let expanded = check(&graph, 0, graph.len() - 1);
+        assert_eq!(expanded, 31);
+
+        // weights steer the path around the expensive first row
+        let mut w = [[1; 4]; 256];
+        (0..16).for_each(|id| w[id] = [1, 50, 1, 1]);
+        let weights = Uniform::from_slice(&graph, &w).unwrap();
+        let (path, _) = astar(&graph, 0, 15, Some(&weights)).unwrap();
+        let dist = Dist::weighted(&graph, 0, &weights).unwrap();
+        assert_eq!(path.path.len(), 18);
+        assert_eq!(dist.shortest_path(&graph, 15).unwrap().path, path.path);
+
+        let graph: Orth<RectCell> = Orth::new(4, 4);
+        assert!(astar(&graph, 0, 15, None).is_err());
+        assert!(astar(&graph, 0, 16, None).is_err());
+    }
+}
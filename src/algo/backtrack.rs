@@ -0,0 +1,128 @@
+use super::{Animation, Canvas, Plain, IN_PATH, SEL_NODE};
+use crate::graphs::{Graph, Node};
+use crate::render::state::graph;
+use crate::render::RenderGraph;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use webp_animation::WebPData;
+
+/// Recursive backtracker: a randomized depth-first search that produces long, winding corridors.
+///
+/// Walks to a random unvisited neighbor until it reaches a dead end, then backs up to the most
+/// recent node that still has unvisited neighbors.  An explicit stack is used instead of recursion,
+/// so large graphs cannot overflow the call stack.
+///
+/// Only nodes reachable from the random starting node are visited.
+pub fn backtracker<G: Graph, R: Rng + ?Sized>(
+    graph: &mut G,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    match graph.random(rng) {
+        Some(start) => run(&mut Plain(graph), start, rng),
+        None => Ok(()),
+    }
+}
+
+/// Animated version of [`backtracker`].
+///
+/// Nodes on the stack are highlighted, with the top of the stack in a lighter color.  Nodes are
+/// cleared as the search backtracks past them.
+pub fn animated_backtracker<G, R>(
+    graph: &mut graph::State<G>,
+    opts: &crate::render::opts::AnimOpts,
+    rng: &mut R,
+) -> Result<WebPData, crate::Error>
+where
+    R: Rng + ?Sized,
+    G: Graph + RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    let start = graph
+        .graph()
+        .random(rng)
+        .ok_or(crate::Error::ZeroSizedGraph())?;
+    let mut anim = Animation::new(graph, opts)?;
+    run(&mut anim, start, rng)?;
+    anim.finish()
+}
+
+fn run<C, R>(canvas: &mut C, start: usize, rng: &mut R) -> Result<(), crate::Error>
+where
+    C: Canvas,
+    R: Rng + ?Sized,
+{
+    canvas.set_bg(start, Some(SEL_NODE));
+    canvas.frame()?;
+
+    let mut stack = vec![start];
+    while let Some(&node) = stack.last() {
+        if let Some(n) = canvas.graph().unlinked_neighbors(node).choose(rng) {
+            let n = *n;
+            canvas.set_bg(node, Some(IN_PATH));
+            canvas.set_bg(n, Some(SEL_NODE));
+            canvas.graph_mut().link(node, n)?;
+            stack.push(n);
+        } else {
+            // backtrack
+            canvas.set_bg(node, None);
+            stack.pop();
+            match stack.last() {
+                Some(&prev) => canvas.set_bg(prev, Some(SEL_NODE)),
+                None => break,
+            }
+        }
+        canvas.frame()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphs::{Graph, Node};
+    use crate::render::opts::AnimOpts;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn algo_backtracker() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Orth::new(8, 8);
+        super::backtracker(&mut graph, rng).unwrap();
+        // a perfect maze: every node is visited and there are no loops
+        assert!(graph.nodes().all(|node| !node.is_empty()));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, graph.len() - 1);
+        graph.render().save("images/tests/rect_backtracker.png")
+    }
+
+    #[test]
+    fn anim_backtracker() -> Result<(), crate::Error> {
+        use std::fs::OpenOptions;
+        use std::io::prelude::*;
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Orth::new(8, 8).build_render_owned().finish();
+        let opts = AnimOpts::default();
+        let bytes = super::animated_backtracker(&mut graph, &opts, rng)?;
+        assert!(graph.node_state().iter().all(|state| state.get().is_none()));
+
+        // the animation builds the same maze as the plain version
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut plain = crate::Orth::new(8, 8);
+        super::backtracker(&mut plain, rng).unwrap();
+        assert!(plain
+            .nodes()
+            .zip(graph.graph().nodes())
+            .all(|(a, b)| a.links().eq(b.links())));
+
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open("images/tests/rect_backtracker.webp")
+            .unwrap();
+        f.write_all(&bytes).expect("Error writing to file");
+        Ok(())
+    }
+}
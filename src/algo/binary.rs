@@ -0,0 +1,157 @@
+use super::{Animation, Canvas, Plain, SEL_NODE};
+use crate::graphs::{Graph, Node};
+use crate::render::state::graph;
+use crate::{Orth, RectCell};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use webp_animation::WebPData;
+
+/// Pair of directions that [`binary_tree`] and [`sidewinder`](super::sidewinder::sidewinder) carve
+/// towards.  Passages along both of those outer walls run the full length of the maze.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Bias {
+    #[default]
+    NE,
+    NW,
+    SE,
+    SW,
+}
+
+impl Bias {
+    /// Side of a [`RectCell`] for the vertical direction
+    pub(crate) fn vertical(&self) -> usize {
+        match self {
+            Self::NE | Self::NW => 0,
+            Self::SE | Self::SW => 2,
+        }
+    }
+
+    /// Side of a [`RectCell`] for the horizontal direction
+    pub(crate) fn horizontal(&self) -> usize {
+        match self {
+            Self::NE | Self::SE => 1,
+            Self::NW | Self::SW => 3,
+        }
+    }
+
+    /// Whether a cell is on the outer wall in the vertical direction.
+    /// Wrapped graphs are treated as if they did not wrap.
+    pub(crate) fn at_vertical_edge(&self, graph: &Orth<RectCell>, id: usize) -> bool {
+        let row = id / graph.width();
+        match self.vertical() {
+            0 => row == 0,
+            _ => row + 1 == graph.height(),
+        }
+    }
+
+    /// Whether a cell is on the outer wall in the horizontal direction.
+    /// Wrapped graphs are treated as if they did not wrap.
+    pub(crate) fn at_horizontal_edge(&self, graph: &Orth<RectCell>, id: usize) -> bool {
+        let col = id % graph.width();
+        match self.horizontal() {
+            1 => col + 1 == graph.width(),
+            _ => col == 0,
+        }
+    }
+}
+
+/// Binary tree: links every cell in one of two directions, chosen at random.
+///
+/// Very fast and needs no memory, but has a strong diagonal texture.
+pub fn binary_tree<R: Rng + ?Sized>(
+    graph: &mut Orth<RectCell>,
+    bias: Bias,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    run(&mut Plain(graph), bias, rng)
+}
+
+/// Animated version of [`binary_tree`]
+pub fn animated_binary_tree<R: Rng + ?Sized>(
+    graph: &mut graph::State<Orth<RectCell>>,
+    bias: Bias,
+    opts: &crate::render::opts::AnimOpts,
+    rng: &mut R,
+) -> Result<WebPData, crate::Error> {
+    if graph.graph().node_ids().is_empty() {
+        return Err(crate::Error::ZeroSizedGraph());
+    }
+    let mut anim = Animation::new(graph, opts)?;
+    run(&mut anim, bias, rng)?;
+    anim.finish()
+}
+
+fn run<C, R>(canvas: &mut C, bias: Bias, rng: &mut R) -> Result<(), crate::Error>
+where
+    C: Canvas<Graph = Orth<RectCell>>,
+    R: Rng + ?Sized,
+{
+    for id in canvas.graph().node_ids() {
+        let mut sides = Vec::with_capacity(2);
+        if !bias.at_vertical_edge(canvas.graph(), id) {
+            sides.push(bias.vertical());
+        }
+        if !bias.at_horizontal_edge(canvas.graph(), id) {
+            sides.push(bias.horizontal());
+        }
+        if let Some(n) = sides
+            .choose(rng)
+            .and_then(|side| canvas.graph().node(id).neighbor(*side))
+        {
+            canvas.set_bg(id, Some(SEL_NODE));
+            canvas.graph_mut().link(id, n)?;
+            canvas.frame()?;
+            canvas.set_bg(id, None);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::opts::AnimOpts;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn algo_binary_tree() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        for bias in [Bias::NE, Bias::NW, Bias::SE, Bias::SW] {
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            let mut graph = Orth::new(8, 8);
+            binary_tree(&mut graph, bias, rng).unwrap();
+            let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+            assert_eq!(links / 2, graph.len() - 1);
+
+            // the outer walls in the bias directions are open corridors
+            let row = if bias.vertical() == 0 { 0 } else { 7 };
+            for col in 0..7 {
+                assert!(graph.node(row * 8 + col).linked_to(row * 8 + col + 1));
+            }
+        }
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(8, 8);
+        binary_tree(&mut graph, Bias::NE, rng).unwrap();
+        graph.render().save("images/tests/rect_binary_tree.png")
+    }
+
+    #[test]
+    fn anim_binary_tree() -> Result<(), crate::Error> {
+        use std::fs::OpenOptions;
+        use std::io::prelude::*;
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(6, 6).build_render_owned().finish();
+        let bytes = animated_binary_tree(&mut graph, Bias::SW, &AnimOpts::default(), rng)?;
+
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open("images/tests/rect_binary_tree.webp")
+            .unwrap();
+        f.write_all(&bytes).expect("Error writing to file");
+        Ok(())
+    }
+}
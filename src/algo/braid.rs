@@ -0,0 +1,135 @@
+use crate::graphs::{Graph, Node};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Remove dead ends by linking each of them to another neighbor with a probability of `p`.
+///
+/// Neighbors that are dead ends themselves are preferred, which removes two dead ends with one link.
+/// Neighbors the graph refuses to link to are skipped.
+/// Returns the number of links added; in a connected maze each one creates a loop.
+pub fn braid<G: Graph, R: Rng + ?Sized>(
+    graph: &mut G,
+    p: f64,
+    rng: &mut R,
+) -> Result<usize, crate::Error> {
+    let p = p.clamp(0.0, 1.0);
+    let mut loops = 0;
+    for node in dead_ends(graph) {
+        // an earlier link may have already removed this dead end
+        if graph.node(node).num_links() != 1 || !rng.gen_bool(p) {
+            continue;
+        }
+        let mut options: Vec<usize> = graph
+            .node(node)
+            .neighbors()
+            .filter(|n| !graph.node(node).linked_to(**n))
+            .copied()
+            .collect();
+        options.shuffle(rng);
+        options.sort_by_key(|n| graph.node(*n).num_links() != 1);
+        for n in options {
+            match graph.link(node, n) {
+                Ok(()) => {
+                    loops += 1;
+                    break;
+                }
+                // the graph may refuse a link in its current state (e.g., a tunnel in a Weave)
+                Err(crate::Error::InvalidNeighbor(_, _)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    Ok(loops)
+}
+
+/// Remove dead ends from the maze by unlinking each of them with a probability of `p`.
+///
+/// Removed nodes are left without any links.  Only dead ends that exist before the call are
+/// removed, so calling it again removes the new dead ends one step further in.  Returns the number
+/// of nodes removed.
+pub fn sparsify<G: Graph, R: Rng + ?Sized>(
+    graph: &mut G,
+    p: f64,
+    rng: &mut R,
+) -> Result<usize, crate::Error> {
+    let p = p.clamp(0.0, 1.0);
+    let mut removed = 0;
+    for node in dead_ends(graph) {
+        // don't remove both ends of an isolated passage
+        if graph.node(node).num_links() != 1 || !rng.gen_bool(p) {
+            continue;
+        }
+        let n = *graph
+            .node(node)
+            .links()
+            .next()
+            .expect("dead ends have one link");
+        graph.unlink(node, n)?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// Nodes with exactly one link
+fn dead_ends<G: Graph>(graph: &G) -> Vec<usize> {
+    graph
+        .nodes()
+        .filter(|node| node.num_links() == 1)
+        .map(|node| node.id())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Orth;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    fn num_links<G: Graph>(graph: &G) -> usize {
+        graph.nodes().map(|node| node.num_links()).sum::<usize>() / 2
+    }
+
+    #[test]
+    fn algo_braid() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(8, 8);
+        crate::algo::backtrack::backtracker(&mut graph, rng).unwrap();
+        assert!(!dead_ends(&graph).is_empty());
+
+        let loops = braid(&mut graph, 1.0, rng).unwrap();
+        assert!(dead_ends(&graph).is_empty());
+        assert_eq!(num_links(&graph), graph.len() - 1 + loops);
+        assert_eq!(braid(&mut graph, 1.0, rng).unwrap(), 0);
+        graph.render().save("images/tests/rect_braid.png")
+    }
+
+    #[test]
+    fn braid_weave() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Weave::new(8, 8);
+        crate::algo::hk::huntkill(&mut graph, rng).unwrap();
+        let loops = braid(&mut graph, 1.0, rng).unwrap();
+        assert!(loops > 0);
+        assert_eq!(num_links(&graph), graph.len() - 1 + loops);
+    }
+
+    #[test]
+    fn algo_sparsify() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(8, 8);
+        crate::algo::backtrack::backtracker(&mut graph, rng).unwrap();
+        assert_eq!(sparsify(&mut graph, 0.0, rng).unwrap(), 0);
+
+        let ends = dead_ends(&graph).len();
+        let removed = sparsify(&mut graph, 1.0, rng).unwrap();
+        assert_eq!(removed, ends);
+        // what remains is still a single tree
+        let remaining = graph.nodes().filter(|node| !node.is_empty()).count();
+        assert_eq!(remaining, graph.len() - removed);
+        assert_eq!(num_links(&graph), remaining - 1);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::graphs::{Graph, Node};
+use crate::{Dist, Path};
+
+/// The two nodes farthest apart in a maze, found by [`diameter`]
+#[derive(Clone, Debug)]
+pub struct Diameter {
+    start: usize,
+    end: usize,
+    path: Path,
+    cyclic: bool,
+}
+
+impl Diameter {
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of steps between the two ends
+    pub fn len(&self) -> usize {
+        self.path.path.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Whether the maze has loops, in which case the ends are only likely to be the farthest apart
+    pub fn cyclic(&self) -> bool {
+        self.cyclic
+    }
+}
+
+/// Longest shortest path of a maze, using two breadth first searches.
+///
+/// The first search finds the node farthest from an arbitrary node, and the second finds the node
+/// farthest from that one.  This is exact for perfect mazes.  Only the part of the graph connected
+/// to the first linked node is searched.
+pub fn diameter<G: Graph>(graph: &G) -> Result<Diameter, crate::Error> {
+    let ids = graph.node_ids();
+    let first = ids
+        .iter()
+        .find(|id| graph.node(**id).num_links() > 0)
+        .or_else(|| ids.first())
+        .copied()
+        .ok_or(crate::Error::ZeroSizedGraph())?;
+
+    let dist = Dist::simple(graph, first);
+    let start = dist.farthest().unwrap_or(first);
+    let dist = Dist::simple(graph, start);
+    let end = dist.farthest().unwrap_or(start);
+    let path = dist.shortest_path(graph, end)?;
+
+    // a tree has one less link than it has nodes
+    let reached: Vec<usize> = ids
+        .into_iter()
+        .filter(|id| dist.dist(*id).is_some())
+        .collect();
+    let links: usize = reached.iter().map(|id| graph.node(*id).num_links()).sum();
+    Ok(Diameter {
+        start,
+        end,
+        path,
+        cyclic: links / 2 >= reached.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::backtrack::backtracker;
+    use crate::algo::braid::braid;
+    use crate::{Orth, RectCell};
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn maze_diameter() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph: Orth<RectCell> = Orth::new(10, 10);
+        backtracker(&mut graph, rng).unwrap();
+
+        let d = diameter(&graph).unwrap();
+        assert!(!d.cyclic());
+        // the same as checking every node
+        let longest = graph
+            .node_ids()
+            .into_iter()
+            .map(|id| Dist::simple(&graph, id).max())
+            .max()
+            .unwrap();
+        assert_eq!(d.len(), longest);
+        assert_eq!(Dist::simple(&graph, d.start()).dist(d.end()), Some(longest));
+        assert_eq!(d.path().step(0), d.start());
+        assert_eq!(d.path().step(d.len()), d.end());
+
+        braid(&mut graph, 0.5, rng).unwrap();
+        assert!(diameter(&graph).unwrap().cyclic());
+
+        let graph: Orth<RectCell> = Orth::new(3, 3);
+        let d = diameter(&graph).unwrap();
+        assert!(d.is_empty());
+        assert!(!d.cyclic());
+    }
+}
@@ -1,9 +1,16 @@
+use crate::graphs::weight::Weights;
 use crate::graphs::{Graph, Node};
 use crate::util::AddUpdate;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 #[derive(Clone, Debug)]
 pub struct Dist {
     dist: Vec<Option<usize>>,
+    /// Node each node was reached from on a shortest path
+    prev: Vec<Option<usize>>,
+    /// Index of the start node closest to each node
+    owner: Vec<Option<usize>>,
     start: usize,
     max: usize,
 }
@@ -13,9 +20,31 @@ impl Dist {
         distance_simple(graph, start)
     }
 
+    /// Distance from the nearest of several start nodes, recording which one owns each node.
+    ///
+    /// Nodes that are equally far from more than one start belong to the first of them.  A start
+    /// listed more than once only owns nodes under its first index: the later duplicates own no region.
+    pub fn multi<G: Graph>(graph: &G, starts: &[usize]) -> Result<Self, crate::Error> {
+        distance_multi(graph, starts)
+    }
+
+    /// Distances along links using the weight of each edge, using Dijkstra's algorithm.
+    ///
+    /// Accepts either [`Uniform`](crate::graphs::weight::Uniform) or
+    /// [`Directed`](crate::graphs::weight::Directed) weights.
+    pub fn weighted<G: Graph, W: Weights + ?Sized>(
+        graph: &G,
+        start: usize,
+        weights: &W,
+    ) -> Result<Self, crate::Error> {
+        distance_weighted(graph, start, weights)
+    }
+
     pub(crate) fn blank<G: Graph>(graph: &G) -> Self {
         Self {
             dist: vec![None; graph.len()],
+            prev: vec![None; graph.len()],
+            owner: vec![None; graph.len()],
             start: 0,
             max: 0,
         }
@@ -28,10 +57,35 @@ impl Dist {
         self.dist[id]
     }
 
+    /// Node before `id` on a shortest path from the start
+    pub fn prev(&self, id: usize) -> Option<usize> {
+        self.prev.get(id).copied().flatten()
+    }
+
+    /// Index into the start nodes of the start that `id` is closest to
+    pub fn owner(&self, id: usize) -> Option<usize> {
+        self.owner.get(id).copied().flatten()
+    }
+
+    /// Number of start nodes
+    pub(crate) fn num_owners(&self) -> usize {
+        self.owner.iter().flatten().max().map_or(0, |o| o + 1)
+    }
+
     pub fn max(&self) -> usize {
         self.max
     }
 
+    /// Node with the largest distance from the start, preferring the lowest id on ties
+    pub fn farthest(&self) -> Option<usize> {
+        self.dist
+            .iter()
+            .enumerate()
+            .filter_map(|(id, d)| d.map(|d| (d, id)))
+            .min_by_key(|(d, id)| (std::cmp::Reverse(*d), *id))
+            .map(|(_, id)| id)
+    }
+
     pub fn set_max(&mut self, max: usize) {
         self.max = max;
     }
@@ -71,19 +125,45 @@ impl Dist {
 }
 
 pub(crate) fn distance_simple<G: Graph>(graph: &G, start: usize) -> Dist {
+    distance_from(graph, &[start])
+}
+
+pub(crate) fn distance_multi<G: Graph>(graph: &G, starts: &[usize]) -> Result<Dist, crate::Error> {
+    if let Some(id) = starts.iter().find(|id| **id >= graph.len()) {
+        return Err(crate::Error::InvalidId(*id, graph.len()));
+    }
+    Ok(distance_from(graph, starts))
+}
+
+/// Breadth first search from every start at once.  Start ids must be valid.
+fn distance_from<G: Graph>(graph: &G, starts: &[usize]) -> Dist {
     let mut dist = Dist::blank(graph);
     let mut max = 0;
-    let mut frontier: Vec<usize> = Vec::with_capacity(G::Node::N);
-    frontier.push(start);
-    dist.start(start);
-    dist.add(start, 0);
+    let mut frontier: Vec<usize> = Vec::with_capacity(G::Node::N * starts.len());
+    for (i, start) in starts.iter().enumerate() {
+        if dist.add(*start, 0).added() {
+            dist.owner[*start] = Some(i);
+            frontier.push(*start);
+        }
+    }
+    if let Some(start) = starts.first() {
+        dist.start(*start);
+    }
 
     while !frontier.is_empty() {
         let mut frontier2: Vec<usize> = Vec::with_capacity(frontier.len() * G::Node::N);
         for cell in &frontier {
             let d = dist.dist(*cell).unwrap();
             for link in graph.node(*cell).links() {
-                if dist.add(*link, d + 1).added() {
+                let added = dist.add(*link, d + 1);
+                // ties go to the earliest start
+                if added.added()
+                    || (dist.dist(*link) == Some(d + 1) && dist.owner[*cell] < dist.owner[*link])
+                {
+                    dist.prev[*link] = Some(*cell);
+                    dist.owner[*link] = dist.owner[*cell];
+                }
+                if added.added() {
                     frontier2.push(*link);
                     if d + 1 > max {
                         max = d + 1;
@@ -98,6 +178,41 @@ pub(crate) fn distance_simple<G: Graph>(graph: &G, start: usize) -> Dist {
     dist
 }
 
+pub(crate) fn distance_weighted<G: Graph, W: Weights + ?Sized>(
+    graph: &G,
+    start: usize,
+    weights: &W,
+) -> Result<Dist, crate::Error> {
+    let mut dist = Dist::blank(graph);
+    let mut done = vec![false; graph.len()];
+    let mut heap = BinaryHeap::new();
+    dist.start(start);
+    dist.add(start, 0);
+    dist.owner[start] = Some(0);
+    heap.push(Reverse((0, start)));
+
+    while let Some(Reverse((d, cell))) = heap.pop() {
+        if done[cell] {
+            continue;
+        }
+        done[cell] = true;
+        dist.max = dist.max.max(d);
+        let node = graph.node(cell);
+        for link in node.links() {
+            let side = node
+                .neighbor_id(*link)
+                .ok_or(crate::Error::InvalidEdge(cell, *link))?;
+            let next = d.saturating_add(weights.weight(cell, side)?);
+            if !dist.add(*link, next).exists() {
+                dist.prev[*link] = Some(cell);
+                dist.owner[*link] = Some(0);
+                heap.push(Reverse((next, *link)));
+            }
+        }
+    }
+    Ok(dist)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +238,82 @@ mod tests {
         };
         dist_renderer.render().save("images/tests/rect_dist.png")
     }
+
+    #[test]
+    fn weighted_dist() {
+        crate::logger(log::LevelFilter::Warn);
+        use crate::graphs::weight::Uniform;
+        use crate::{Orth, RectCell};
+        let graph: Orth<RectCell> = Orth::new_linked(3, 3);
+
+        // unit weights give the same distances as a breadth first search
+        let weights = Uniform::from_slice(&graph, &[[1; 4]; 9]).unwrap();
+        let simple = Dist::simple(&graph, 0);
+        let weighted = Dist::weighted(&graph, 0, &weights).unwrap();
+        assert!((0..9).all(|id| simple.dist(id) == weighted.dist(id)));
+        assert_eq!(simple.max(), weighted.max());
+
+        // walking past node 1 costs more than going around it
+        let mut w = [[1; 4]; 9];
+        w[0] = [1, 10, 1, 1];
+        w[1] = [10; 4];
+        let weights = Uniform::from_slice(&graph, &w).unwrap();
+        let dist = Dist::weighted(&graph, 0, &weights).unwrap();
+        assert_eq!(dist.dist(1), Some(10));
+        assert_eq!(dist.dist(2), Some(4));
+        assert_eq!(dist.prev(0), None);
+        let path = dist.shortest_path(&graph, 2).unwrap();
+        assert_eq!(path.path, vec![0, 3, 4, 5, 2]);
+        assert_eq!(path.step_num(2), Some(4));
+    }
+
+    #[test]
+    fn multi_dist() -> Result<(), image::ImageError> {
+        crate::logger(log::LevelFilter::Warn);
+        use crate::render::opts::blend::Blend;
+        use crate::{Orth, RectCell};
+        use rand::SeedableRng;
+        use rand_xoshiro::SplitMix64;
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph: Orth<RectCell> = Orth::new(10, 10);
+        crate::algo::backtrack::backtracker(&mut graph, rng).unwrap();
+
+        let starts = [0, 9, 90, 99];
+        let multi = Dist::multi(&graph, &starts).unwrap();
+        let single: Vec<Dist> = starts.iter().map(|s| Dist::simple(&graph, *s)).collect();
+        for id in graph.node_ids() {
+            // the distance to the nearest start, which owns the node
+            let nearest = single.iter().filter_map(|d| d.dist(id)).min();
+            assert_eq!(multi.dist(id), nearest);
+            let owner = multi.owner(id).unwrap();
+            assert_eq!(single[owner].dist(id), nearest);
+            // ties go to the first start
+            assert!(single[..owner].iter().all(|d| d.dist(id) > nearest));
+            // paths lead back to the owner
+            let path = multi.shortest_path(&graph, id).unwrap();
+            assert_eq!(path.step(0), starts[owner]);
+        }
+        assert_eq!(multi.owner(99), Some(3));
+        assert_eq!(Dist::simple(&graph, 5).owner(50), Some(0));
+        assert!(matches!(
+            Dist::multi(&graph, &[0, 100]),
+            Err(crate::Error::InvalidId(100, 100))
+        ));
+        // a duplicate start owns nothing
+        let dup = Dist::multi(&graph, &[0, 99, 0]).unwrap();
+        assert!(graph.node_ids().iter().all(|id| dup.owner(*id) != Some(2)));
+
+        let state = graph.build_render().finish();
+        let blends = vec![
+            Blend::blend_rgb([true, false, false]),
+            Blend::blend_rgb([false, false, true]),
+        ];
+        state
+            .build_regions()
+            .multi_dist(&starts)
+            .unwrap()
+            .blends(blends)
+            .render()
+            .save("images/tests/rect_regions.png")
+    }
 }
@@ -0,0 +1,312 @@
+use super::{Animation, Canvas, Plain, IN_PATH, SEL_NODE};
+use crate::graphs::{Graph, Node};
+use crate::render::state::graph;
+use crate::{Orth, RectCell};
+use rand::Rng;
+use webp_animation::WebPData;
+
+/// Options for [`division`]
+#[derive(Clone, Debug)]
+pub struct DivisionOpts {
+    room_height: usize,
+    room_width: usize,
+    room_chance: f64,
+    horizontal_bias: Option<f64>,
+}
+
+impl Default for DivisionOpts {
+    fn default() -> Self {
+        Self {
+            room_height: 0,
+            room_width: 0,
+            room_chance: 0.0,
+            horizontal_bias: None,
+        }
+    }
+}
+
+impl DivisionOpts {
+    /// Regions no larger than `height` by `width` are left undivided as open rooms with a probability of `chance`
+    pub fn rooms(mut self, height: usize, width: usize, chance: f64) -> Self {
+        self.room_height = height;
+        self.room_width = width;
+        self.room_chance = chance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Probability of dividing a region with a horizontal wall.
+    ///
+    /// Without a bias, regions are divided across their longer side and square regions are divided
+    /// either way.
+    pub fn horizontal_bias(mut self, bias: f64) -> Self {
+        self.horizontal_bias = Some(bias.clamp(0.0, 1.0));
+        self
+    }
+
+    pub fn room_height(&self) -> usize {
+        self.room_height
+    }
+
+    pub fn room_width(&self) -> usize {
+        self.room_width
+    }
+
+    pub fn room_chance(&self) -> f64 {
+        self.room_chance
+    }
+
+    pub fn bias(&self) -> Option<f64> {
+        self.horizontal_bias
+    }
+}
+
+/// Part of the grid that still needs dividing
+#[derive(Clone, Copy, Debug)]
+struct Region {
+    row: usize,
+    col: usize,
+    height: usize,
+    width: usize,
+}
+
+/// Recursive division: adds walls instead of carving passages.
+///
+/// Starting from a fully linked graph (see [`Orth::new_linked`]), each region is split in two by a wall
+/// with a single gap, until regions are one cell wide or are left as rooms.
+///
+/// Returns an error without changing the graph if it is masked, wraps around, or any node is not
+/// linked to all of its neighbors.
+pub fn division<R: Rng + ?Sized>(
+    graph: &mut Orth<RectCell>,
+    opts: &DivisionOpts,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    check(graph)?;
+    run(&mut Plain(graph), opts, rng)
+}
+
+/// Animated version of [`division`], adding one wall per frame
+pub fn animated_division<R: Rng + ?Sized>(
+    graph: &mut graph::State<Orth<RectCell>>,
+    opts: &DivisionOpts,
+    anim_opts: &crate::render::opts::AnimOpts,
+    rng: &mut R,
+) -> Result<WebPData, crate::Error> {
+    if graph.graph().node_ids().is_empty() {
+        return Err(crate::Error::ZeroSizedGraph());
+    }
+    check(graph.graph())?;
+    let mut anim = Animation::new(graph, anim_opts)?;
+    anim.frame()?;
+    run(&mut anim, opts, rng)?;
+    anim.finish()
+}
+
+/// Division only adds walls inside the grid, so it needs an unmasked, unwrapped, fully linked graph
+fn check(graph: &Orth<RectCell>) -> Result<(), crate::Error> {
+    if graph.mask().is_some() {
+        return Err(crate::Error::MaskedGraph());
+    }
+    if graph
+        .node_ids()
+        .into_iter()
+        .any(|id| (0..4).any(|n| graph.is_seam(id, n)))
+    {
+        return Err(crate::Error::WrappedGraph());
+    }
+    match graph
+        .nodes()
+        .find(|node| node.neighbors().any(|n| !node.linked_to(*n)))
+    {
+        Some(node) => Err(crate::Error::UnlinkedGraph(node.id())),
+        None => Ok(()),
+    }
+}
+
+fn run<C, R>(canvas: &mut C, opts: &DivisionOpts, rng: &mut R) -> Result<(), crate::Error>
+where
+    C: Canvas<Graph = Orth<RectCell>>,
+    R: Rng + ?Sized,
+{
+    let columns = canvas.graph().width();
+    let mut regions = vec![Region {
+        row: 0,
+        col: 0,
+        height: canvas.graph().height(),
+        width: canvas.graph().width(),
+    }];
+
+    while let Some(region) = regions.pop() {
+        let Region {
+            row,
+            col,
+            height,
+            width,
+        } = region;
+        if height < 2 || width < 2 {
+            continue;
+        }
+        if height <= opts.room_height && width <= opts.room_width && rng.gen_bool(opts.room_chance)
+        {
+            continue;
+        }
+
+        let horizontal = match opts.horizontal_bias {
+            Some(bias) => rng.gen_bool(bias),
+            None if height == width => rng.gen_bool(0.5),
+            None => height > width,
+        };
+
+        // cells on the near side of the wall, and the direction to the cell on the far side
+        let (wall, gap, step): (Vec<usize>, usize, usize) = if horizontal {
+            let at = row + rng.gen_range(0..height - 1);
+            regions.push(Region {
+                row: at + 1,
+                height: row + height - at - 1,
+                ..region
+            });
+            regions.push(Region {
+                height: at - row + 1,
+                ..region
+            });
+            let cells = (col..col + width).map(|c| at * columns + c).collect();
+            (cells, rng.gen_range(0..width), columns)
+        } else {
+            let at = col + rng.gen_range(0..width - 1);
+            regions.push(Region {
+                col: at + 1,
+                width: col + width - at - 1,
+                ..region
+            });
+            regions.push(Region {
+                width: at - col + 1,
+                ..region
+            });
+            let cells = (row..row + height).map(|r| r * columns + at).collect();
+            (cells, rng.gen_range(0..height), 1)
+        };
+
+        for (i, &id) in wall.iter().enumerate() {
+            if i == gap {
+                canvas.set_bg(id, Some(SEL_NODE));
+                canvas.set_bg(id + step, Some(SEL_NODE));
+            } else {
+                canvas.set_bg(id, Some(IN_PATH));
+                canvas.set_bg(id + step, Some(IN_PATH));
+                if canvas.graph().node(id).linked_to(id + step) {
+                    canvas.graph_mut().unlink(id, id + step)?;
+                }
+            }
+        }
+        canvas.frame()?;
+        for id in wall {
+            canvas.set_bg(id, None);
+            canvas.set_bg(id + step, None);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::opts::AnimOpts;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    fn num_links(graph: &Orth<RectCell>) -> usize {
+        graph.nodes().map(|node| node.num_links()).sum::<usize>() / 2
+    }
+
+    #[test]
+    fn algo_division() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new_linked(8, 8);
+        division(&mut graph, &DivisionOpts::default(), rng).unwrap();
+        // a perfect maze
+        assert_eq!(num_links(&graph), graph.len() - 1);
+        graph.render().save("images/tests/rect_division.png")
+    }
+
+    #[test]
+    fn division_opts() {
+        crate::logger(log::LevelFilter::Warn);
+        // rooms leave loops behind
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new_linked(8, 8);
+        division(&mut graph, &DivisionOpts::default().rooms(4, 4, 1.0), rng).unwrap();
+        assert!(num_links(&graph) > graph.len() - 1);
+
+        // always dividing horizontally gives rows linked by a single passage
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new_linked(8, 8);
+        let opts = DivisionOpts::default().horizontal_bias(1.0);
+        division(&mut graph, &opts, rng).unwrap();
+        for row in 0..7 {
+            let down = (0..8)
+                .filter(|col| graph.node(row * 8 + col).linked_side(2))
+                .count();
+            assert_eq!(down, 1);
+        }
+    }
+
+    #[test]
+    fn division_invalid() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let opts = DivisionOpts::default();
+        let mut graph = Orth::new_linked(4, 4);
+        graph.unlink(5, 6).unwrap();
+        assert!(matches!(
+            division(&mut graph, &opts, rng),
+            Err(crate::Error::UnlinkedGraph(5))
+        ));
+        assert_eq!(num_links(&graph), 23);
+
+        let mut graph: Orth<RectCell> = Orth::cylinder(4, 4);
+        for id in 0..16 {
+            let neighbors: Vec<usize> = graph.node(id).neighbors().copied().collect();
+            for n in neighbors {
+                if !graph.node(id).linked_to(n) {
+                    graph.link(id, n).unwrap();
+                }
+            }
+        }
+        assert!(matches!(
+            division(&mut graph, &opts, rng),
+            Err(crate::Error::WrappedGraph())
+        ));
+
+        let mut graph: Orth<RectCell> = Orth::masked(crate::Mask::new(4, 4));
+        assert!(matches!(
+            division(&mut graph, &opts, rng),
+            Err(crate::Error::MaskedGraph())
+        ));
+    }
+
+    #[test]
+    fn anim_division() -> Result<(), crate::Error> {
+        use std::fs::OpenOptions;
+        use std::io::prelude::*;
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new_linked(8, 8).build_render_owned().finish();
+        let bytes = animated_division(
+            &mut graph,
+            &DivisionOpts::default(),
+            &AnimOpts::default(),
+            rng,
+        )?;
+        assert_eq!(num_links(graph.graph()), 63);
+
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open("images/tests/rect_division.webp")
+            .unwrap();
+        f.write_all(&bytes).expect("Error writing to file");
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use crate::graphs::{Graph, Node};
+use crate::render::opts::GraphOpts;
+use crate::{Orth, RectCell};
+use image::RgbaImage;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Chance of linking two neighboring cells in different sets, and of linking a cell to the row below
+const JOIN: f64 = 0.5;
+
+/// A finished row of an [`Eller`] maze
+#[derive(Clone, Debug)]
+pub struct Row {
+    index: usize,
+    east: Vec<bool>,
+    south: Vec<bool>,
+    last: bool,
+}
+
+impl Row {
+    /// Position of the row, starting from 0 at the top
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn width(&self) -> usize {
+        self.east.len()
+    }
+
+    /// Whether the cell in column `col` is linked to the cell on its right
+    pub fn east(&self, col: usize) -> bool {
+        self.east[col]
+    }
+
+    /// Whether the cell in column `col` is linked to the cell below it
+    pub fn south(&self, col: usize) -> bool {
+        self.south[col]
+    }
+
+    /// Whether this is the bottom row of the maze
+    pub fn is_last(&self) -> bool {
+        self.last
+    }
+}
+
+/// Eller's algorithm: generates a perfect [`Orth`] maze one row at a time.
+///
+/// Only the current row is kept in memory, so mazes can have any number of rows.  The iterator never
+/// ends; call [`finish`](Eller::finish) to get a bottom row that closes the maze.
+///
+/// ```ignore
+/// let mut rows = Eller::new(20, rng);
+/// let renderer = RowRenderer::default();
+/// for row in rows.by_ref().take(1_000_000) {
+///     let strip = renderer.render(&row);
+/// }
+/// let strip = renderer.render(&rows.finish());
+/// ```
+#[derive(Debug)]
+pub struct Eller<'r, R: Rng + ?Sized> {
+    rng: &'r mut R,
+    index: usize,
+    /// Set of each cell in the current row; cells in the same set are already connected
+    sets: Vec<usize>,
+    next_set: usize,
+}
+
+impl<'r, R: Rng + ?Sized> Eller<'r, R> {
+    pub fn new(width: usize, rng: &'r mut R) -> Self {
+        Self {
+            rng,
+            index: 0,
+            sets: (0..width).collect(),
+            next_set: width,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// Finish the maze with a bottom row that connects every remaining set
+    pub fn finish(mut self) -> Row {
+        let width = self.width();
+        let east = self.join(true);
+        Row {
+            index: self.index,
+            east,
+            south: vec![false; width],
+            last: true,
+        }
+    }
+
+    /// Link neighboring cells from different sets: all of them when `all` is set, otherwise at random
+    fn join(&mut self, all: bool) -> Vec<bool> {
+        let width = self.width();
+        let mut east: Vec<bool> = (0..width.saturating_sub(1))
+            .map(|col| {
+                let join = self.sets[col] != self.sets[col + 1] && (all || self.rng.gen_bool(JOIN));
+                if join {
+                    self.merge(col);
+                }
+                join
+            })
+            .collect();
+        east.resize(width, false);
+        east
+    }
+
+    /// Put the cell right of `col` (and the rest of its set) into the same set as `col`
+    fn merge(&mut self, col: usize) {
+        let (keep, replace) = (self.sets[col], self.sets[col + 1]);
+        self.sets
+            .iter_mut()
+            .filter(|set| **set == replace)
+            .for_each(|set| *set = keep);
+    }
+}
+
+impl<R: Rng + ?Sized> Iterator for Eller<'_, R> {
+    type Item = Row;
+
+    fn next(&mut self) -> Option<Row> {
+        let width = self.width();
+
+        let east = self.join(false);
+
+        // every set needs at least one link to the next row
+        let mut cols: Vec<usize> = (0..width).collect();
+        cols.sort_by_key(|col| self.sets[*col]);
+        let mut south = vec![false; width];
+        let mut start = 0;
+        while start < width {
+            let set = self.sets[cols[start]];
+            let len = cols[start..]
+                .iter()
+                .take_while(|col| self.sets[**col] == set)
+                .count();
+            let group = &cols[start..start + len];
+            start += len;
+            for col in group {
+                south[*col] = self.rng.gen_bool(JOIN);
+            }
+            if !group.iter().any(|col| south[*col]) {
+                south[*group.choose(self.rng).expect("groups are never empty")] = true;
+            }
+        }
+
+        // cells that were not linked from above start new sets
+        for (col, down) in south.iter().enumerate() {
+            if !down {
+                self.sets[col] = self.next_set;
+                self.next_set += 1;
+            }
+        }
+
+        let row = Row {
+            index: self.index,
+            east,
+            south,
+            last: false,
+        };
+        self.index += 1;
+        Some(row)
+    }
+}
+
+/// Generate a maze with Eller's algorithm on an unlinked, unmasked `Orth` graph.
+///
+/// Returns an error without changing the graph if it is masked or any node already has links.
+pub fn eller<R: Rng + ?Sized>(graph: &mut Orth<RectCell>, rng: &mut R) -> Result<(), crate::Error> {
+    if graph.mask().is_some() {
+        return Err(crate::Error::MaskedGraph());
+    }
+    if let Some(node) = graph.nodes().find(|node| !node.is_empty()) {
+        return Err(crate::Error::LinkedGraph(node.id()));
+    }
+    let (height, width) = (graph.height(), graph.width());
+    if height == 0 {
+        return Ok(());
+    }
+    let mut rows = Eller::new(width, rng);
+    let link_row = |graph: &mut Orth<RectCell>, row: &Row| -> Result<(), crate::Error> {
+        let start = row.index() * width;
+        for col in 0..width {
+            if col + 1 < width && row.east(col) {
+                graph.link(start + col, start + col + 1)?;
+            }
+            if row.south(col) {
+                graph.link(start + col, start + col + width)?;
+            }
+        }
+        Ok(())
+    };
+    for row in rows.by_ref().take(height - 1) {
+        link_row(graph, &row)?;
+    }
+    link_row(graph, &rows.finish())
+}
+
+/// Renders [`Eller`] rows as separate image strips, for mazes too tall to render as one image.
+///
+/// Stacking the strips from top to bottom gives the same image as rendering the whole maze at once.
+/// The first strip includes the top padding and the last strip includes the bottom padding.  When
+/// text is shown, cells are labelled by column.
+#[derive(Clone, Debug, Default)]
+pub struct RowRenderer {
+    opts: GraphOpts,
+}
+
+impl RowRenderer {
+    pub fn new(opts: GraphOpts) -> Self {
+        Self { opts }
+    }
+
+    pub fn opts(&self) -> &GraphOpts {
+        &self.opts
+    }
+
+    /// Render a single row
+    pub fn render(&self, row: &Row) -> RgbaImage {
+        let width = row.width();
+        let size = self.opts.size();
+        let (pad, block_height) = (size.padding(), size.block_height() + 1);
+
+        // render the row above a placeholder row, so walls to the next row are drawn as inner edges
+        let rows = if row.is_last() { 1 } else { 2 };
+        let mut graph: Orth<RectCell> = Orth::new(rows, width);
+        for col in 0..width {
+            if col + 1 < width && row.east(col) {
+                let _ = graph.link(col, col + 1);
+            }
+            if rows == 2 && row.south(col) {
+                let _ = graph.link(col, col + width);
+            }
+        }
+        let image = graph.build_render().opts(&self.opts).finish().render();
+
+        let top = if row.index() == 0 { 0 } else { pad };
+        let bottom = if row.is_last() {
+            image.height()
+        } else {
+            pad + block_height
+        };
+        image::imageops::crop_imm(&image, 0, top, image.width(), bottom - top).to_image()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn eller_rows() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(10, 8);
+        eller(&mut graph, rng).unwrap();
+        assert!(graph.nodes().all(|node| !node.is_empty()));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, graph.len() - 1);
+
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut rows = Eller::new(8, rng);
+        assert_eq!(rows.by_ref().take(1000).count(), 1000);
+        assert_eq!(rows.sets.len(), 8);
+        assert_eq!(rows.finish().index(), 1000);
+    }
+
+    #[test]
+    fn eller_invalid() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(4, 4);
+        graph.link(5, 6).unwrap();
+        assert!(matches!(
+            eller(&mut graph, rng),
+            Err(crate::Error::LinkedGraph(5))
+        ));
+        assert_eq!(graph.nodes().filter(|node| !node.is_empty()).count(), 2);
+
+        let mask = crate::Mask::new(4, 4);
+        let mut graph: Orth<RectCell> = Orth::masked(mask);
+        assert!(matches!(
+            eller(&mut graph, rng),
+            Err(crate::Error::MaskedGraph())
+        ));
+    }
+
+    #[test]
+    fn eller_strips() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let opts = GraphOpts::build()
+            .text(crate::render::opts::Text::build().show(false).build())
+            .build();
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(6, 8);
+        eller(&mut graph, rng).unwrap();
+        let full = graph.build_render().opts(&opts).finish().render();
+
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut rows = Eller::new(8, rng);
+        let renderer = RowRenderer::new(opts);
+        let mut strips: Vec<RgbaImage> = rows
+            .by_ref()
+            .take(5)
+            .map(|row| renderer.render(&row))
+            .collect();
+        strips.push(renderer.render(&rows.finish()));
+
+        let mut stacked = RgbaImage::new(full.width(), full.height());
+        let mut y = 0;
+        for strip in &strips {
+            image::imageops::replace(&mut stacked, strip, 0, y as i64);
+            y += strip.height();
+        }
+        assert_eq!(y, full.height());
+        assert!(stacked == full);
+        stacked.save("images/tests/rect_eller.png")
+    }
+}
@@ -0,0 +1,174 @@
+use super::components;
+use crate::graphs::Graph;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+/// Chooses which active node [`growing_tree`] grows from next.
+///
+/// Closures taking the number of active nodes and an rng can be used as a strategy:
+///
+/// ```ignore
+/// // always pick the second newest node when possible
+/// growing_tree(&mut graph, &mut |len: usize, _: &mut dyn RngCore| len.saturating_sub(2), rng)?;
+/// ```
+pub trait Strategy {
+    /// Index into the active nodes, which are ordered from oldest to newest.  `len` is never 0.
+    fn select<R: Rng + ?Sized>(&mut self, len: usize, rng: &mut R) -> usize;
+}
+
+impl<F> Strategy for F
+where
+    F: FnMut(usize, &mut dyn RngCore) -> usize,
+{
+    fn select<R: Rng + ?Sized>(&mut self, len: usize, mut rng: &mut R) -> usize {
+        self(len, &mut rng).min(len - 1)
+    }
+}
+
+/// Built-in strategies for [`growing_tree`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Select {
+    /// Long winding corridors, like the recursive backtracker
+    Newest,
+    /// Many short dead ends, like Prim's algorithm
+    Random,
+    /// Long straight corridors from the start
+    Oldest,
+    Middle,
+}
+
+impl Strategy for Select {
+    fn select<R: Rng + ?Sized>(&mut self, len: usize, rng: &mut R) -> usize {
+        match self {
+            Self::Newest => len - 1,
+            Self::Random => rng.gen_range(0..len),
+            Self::Oldest => 0,
+            Self::Middle => len / 2,
+        }
+    }
+}
+
+/// A weighted mix of built-in strategies, e.g. 75% newest and 25% random
+#[derive(Clone, Debug)]
+pub struct Mix {
+    choices: Vec<Select>,
+    weights: WeightedIndex<f64>,
+}
+
+impl Mix {
+    /// Create a mix from pairs of weights and strategies; weights do not need to add up to 1
+    pub fn new(choices: &[(f64, Select)]) -> Result<Self, crate::Error> {
+        let weights = WeightedIndex::new(choices.iter().map(|(weight, _)| *weight))
+            .map_err(|_| crate::Error::InvalidWeights())?;
+        Ok(Self {
+            choices: choices.iter().map(|(_, select)| *select).collect(),
+            weights,
+        })
+    }
+}
+
+impl Strategy for Mix {
+    fn select<R: Rng + ?Sized>(&mut self, len: usize, rng: &mut R) -> usize {
+        let i = self.weights.sample(rng);
+        self.choices[i].select(len, rng)
+    }
+}
+
+/// Growing tree: grows the maze from a list of active nodes, using `strategy` to pick which one to
+/// grow from next.
+///
+/// The chosen node is linked to a random unvisited neighbor, which becomes active, or is removed
+/// from the list when it has none.  Each connected component of the graph gets its own tree.
+pub fn growing_tree<G, S, R>(
+    graph: &mut G,
+    strategy: &mut S,
+    rng: &mut R,
+) -> Result<(), crate::Error>
+where
+    G: Graph,
+    S: Strategy,
+    R: Rng + ?Sized,
+{
+    for group in components(graph) {
+        let mut active = vec![*group.choose(rng).expect("components are never empty")];
+        while !active.is_empty() {
+            let i = strategy.select(active.len(), rng);
+            let node = active[i];
+            match graph.unlinked_neighbors(node).choose(rng) {
+                Some(&n) => {
+                    graph.link(node, n)?;
+                    active.push(n);
+                }
+                None => {
+                    active.remove(i);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::Node;
+    use crate::{Orth, RectCell};
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    fn dead_ends(graph: &Orth<RectCell>) -> usize {
+        graph.nodes().filter(|node| node.num_links() == 1).count()
+    }
+
+    fn assert_spanning_tree(graph: &Orth<RectCell>) {
+        assert!(graph.nodes().all(|node| !node.is_empty()));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, graph.len() - 1);
+    }
+
+    #[test]
+    fn algo_growing_tree() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let mut ends = Vec::new();
+        for mut select in [
+            Select::Newest,
+            Select::Random,
+            Select::Oldest,
+            Select::Middle,
+        ] {
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            let mut graph = Orth::new(12, 12);
+            growing_tree(&mut graph, &mut select, rng).unwrap();
+            assert_spanning_tree(&graph);
+            ends.push(dead_ends(&graph));
+        }
+        // backtracker-like mazes have far fewer dead ends than Prim-like mazes
+        assert!(ends[0] * 2 < ends[1]);
+
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(12, 12);
+        let mut mix = Mix::new(&[(0.75, Select::Newest), (0.25, Select::Random)]).unwrap();
+        growing_tree(&mut graph, &mut mix, rng).unwrap();
+        assert_spanning_tree(&graph);
+        graph.render().save("images/tests/rect_growing_tree.png")
+    }
+
+    #[test]
+    fn growing_tree_closure() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(6, 6);
+        let mut calls = 0;
+        let mut strategy = |len: usize, rng: &mut dyn RngCore| {
+            calls += 1;
+            rng.gen_range(0..len)
+        };
+        growing_tree(&mut graph, &mut strategy, rng).unwrap();
+        assert_spanning_tree(&graph);
+        assert!(calls >= graph.len());
+
+        assert!(Mix::new(&[]).is_err());
+        assert!(Mix::new(&[(-1.0, Select::Newest)]).is_err());
+    }
+}
@@ -1,44 +1,146 @@
+use super::{Animation, Canvas, Plain, IN_PATH, SEL_NODE};
 use crate::graphs::{Graph, Node};
 use crate::render::state::graph;
 use crate::render::RenderGraph;
 use rand::seq::SliceRandom;
 use rand::Rng;
-use webp_animation::{Encoder, WebPData};
+use std::collections::VecDeque;
+use webp_animation::WebPData;
 
-pub fn huntkill<G: Graph, R: Rng + ?Sized>(graph: &mut G, rng: &mut R) -> Result<(), crate::Error> {
-    if let Some(mut node) = graph.random(rng) {
-        loop {
-            if let Some(n) = graph.unlinked_neighbors(node).choose(rng) {
-                graph.link(node, *n)?;
-                node = *n;
-            } else if let Some(n) = hunt(graph, rng)? {
-                node = n;
-            } else {
-                break;
-            }
+/// Order that the hunt phase of [`huntkill_with`] searches for unvisited nodes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HuntOrder {
+    /// Nodes in id order (row by row for grids)
+    #[default]
+    RowScan,
+    /// Nodes in a random order, chosen once per maze
+    Random,
+    /// Nodes closest to the end of the last walk
+    Nearest,
+}
+
+/// Options for the hunt phase of [`huntkill_with`] and [`animated_huntkill_with`]
+#[derive(Clone, Debug, Default)]
+pub struct HuntOpts {
+    order: HuntOrder,
+    resume: bool,
+}
+
+impl HuntOpts {
+    pub fn new(order: HuntOrder) -> Self {
+        Self {
+            order,
+            resume: false,
         }
     }
-    Ok(())
+
+    /// Start each scan where the last one left off instead of from the beginning.
+    ///
+    /// Nodes never become unvisited again, so this skips nodes that are already known to be visited.
+    /// Has no effect on [`HuntOrder::Nearest`].
+    pub fn resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    pub fn order(&self) -> HuntOrder {
+        self.order
+    }
+
+    pub fn resumes(&self) -> bool {
+        self.resume
+    }
 }
 
-fn hunt<G: Graph, R: Rng + ?Sized>(
-    graph: &mut G,
-    rng: &mut R,
-) -> Result<Option<usize>, crate::Error> {
-    for node in graph.node_ids() {
-        // find first cell without any links
-        if graph.node(node).is_empty() {
-            // choose a random cell that has links
-            if let Some(n) = graph.linked_neighbors(node).choose(rng) {
-                return graph.link(node, *n).map(|_| Some(*n));
+/// Finds unvisited nodes next to the maze
+#[derive(Clone, Debug)]
+struct Hunter {
+    opts: HuntOpts,
+    /// Nodes in scan order
+    scan: Vec<usize>,
+    /// Every node before this position in `scan` has been visited
+    pos: usize,
+}
+
+impl Hunter {
+    fn new<G: Graph, R: Rng + ?Sized>(graph: &G, opts: &HuntOpts, rng: &mut R) -> Self {
+        let scan = match opts.order {
+            HuntOrder::RowScan => graph.node_ids(),
+            HuntOrder::Random => {
+                let mut ids = graph.node_ids();
+                ids.shuffle(rng);
+                ids
             }
+            HuntOrder::Nearest => Vec::new(),
+        };
+        Self {
+            opts: opts.clone(),
+            scan,
+            pos: 0,
         }
     }
-    Ok(None)
+
+    /// Find an unvisited node with a visited neighbor, given the node where the last walk ended
+    fn find<G: Graph>(&mut self, graph: &G, last: usize) -> Option<usize> {
+        if self.opts.order == HuntOrder::Nearest {
+            return Self::nearest(graph, last);
+        }
+
+        let start = if self.opts.resume { self.pos } else { 0 };
+        for i in start..self.scan.len() {
+            let node = self.scan[i];
+            // find first cell without any links
+            if !graph.node(node).is_empty() {
+                if self.opts.resume && i == self.pos {
+                    self.pos += 1;
+                }
+            } else if !graph.linked_neighbors(node).is_empty() {
+                return Some(node);
+            }
+        }
+        None
+    }
+
+    /// Breadth first search from `last`, ignoring walls
+    fn nearest<G: Graph>(graph: &G, last: usize) -> Option<usize> {
+        let mut seen = vec![false; graph.len()];
+        let mut queue = VecDeque::from([last]);
+        seen[last] = true;
+        while let Some(node) = queue.pop_front() {
+            if graph.node(node).is_empty() && !graph.linked_neighbors(node).is_empty() {
+                return Some(node);
+            }
+            for n in graph.node(node).neighbors() {
+                if !seen[*n] {
+                    seen[*n] = true;
+                    queue.push_back(*n);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Hunt-and-kill: random walks through unvisited nodes, followed by a hunt for an unvisited node next
+/// to the maze when a walk reaches a dead end.
+///
+/// Hunts scan the nodes in id order; use [`huntkill_with`] for other options.
+pub fn huntkill<G: Graph, R: Rng + ?Sized>(graph: &mut G, rng: &mut R) -> Result<(), crate::Error> {
+    huntkill_with(graph, &HuntOpts::default(), rng)
 }
 
-const SEL_NODE: image::Rgba<u8> = crate::color::LIGHT_GREEN;
-const IN_PATH: image::Rgba<u8> = crate::color::GREEN;
+/// [`huntkill`] with options for the hunt phase
+pub fn huntkill_with<G: Graph, R: Rng + ?Sized>(
+    graph: &mut G,
+    opts: &HuntOpts,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    let mut hunter = Hunter::new(graph, opts, rng);
+    match graph.random(rng) {
+        Some(start) => run(&mut Plain(graph), &mut hunter, start, rng),
+        None => Ok(()),
+    }
+}
 
 pub fn animated_huntkill<G, R>(
     graph: &mut graph::State<G>,
@@ -50,91 +152,91 @@ where
     G: Graph + RenderGraph + Clone + std::fmt::Debug,
     <G::Node as Node>::Block: Clone + std::fmt::Debug,
 {
-    use crate::render::RenderState;
-
-    let mut timestamp = 0i32;
-    let mut encoder: Encoder = Encoder::new_with_options(graph.size(), opts.encoder_options())?;
-
-    if let Some(mut node) = graph.graph().random(rng) {
-        graph.set_bg(node, Some(SEL_NODE));
-        let image = graph.render();
-        encoder.add_frame(&*image, timestamp)?;
-        timestamp += opts.frame_time();
-        graph.set_bg(node, Some(IN_PATH));
-        let image = graph.render();
-        encoder.add_frame(&*image, timestamp)?;
-        timestamp += opts.frame_time();
-
-        loop {
-            if let Some(n) = graph.graph().unlinked_neighbors(node).choose(rng) {
-                graph.set_bg(*n, Some(SEL_NODE));
-                let image = graph.render();
-                encoder.add_frame(&*image, timestamp)?;
-                timestamp += opts.frame_time();
-                graph.set_bg(*n, Some(IN_PATH));
-
-                graph.graph_mut().link(node, *n)?;
-
-                let image = graph.render();
-                encoder.add_frame(&*image, timestamp)?;
-                timestamp += opts.frame_time();
-
-                node = *n;
-            } else if let Some(n) = animated_hunt(graph, &mut timestamp, &mut encoder, opts, rng)? {
-                graph.set_bg(n, Some(SEL_NODE));
-                let image = graph.render();
-                encoder.add_frame(&*image, timestamp)?;
-                timestamp += opts.frame_time();
-
-                graph.set_bg(n, Some(IN_PATH));
-                let image = graph.render();
-                encoder.add_frame(&*image, timestamp)?;
-                timestamp += opts.frame_time();
-
-                node = n;
-            } else {
-                break;
-            }
-        }
-
-        encoder
-            .finalize(timestamp + opts.repeat_delay())
-            .map_err(crate::Error::from)
-    } else {
-        Err(crate::Error::ZeroSizedGraph())
-    }
+    animated_huntkill_with(graph, &HuntOpts::default(), opts, rng)
 }
 
-fn animated_hunt<G, R>(
+/// Animated version of [`huntkill_with`]
+pub fn animated_huntkill_with<G, R>(
     graph: &mut graph::State<G>,
-    timestamp: &mut i32,
-    encoder: &mut Encoder,
+    hunt_opts: &HuntOpts,
     opts: &crate::render::opts::AnimOpts,
     rng: &mut R,
-) -> Result<Option<usize>, crate::Error>
+) -> Result<WebPData, crate::Error>
 where
     R: Rng + ?Sized,
     G: Graph + RenderGraph + Clone + std::fmt::Debug,
     <G::Node as Node>::Block: Clone + std::fmt::Debug,
 {
-    for node in graph.graph().node_ids() {
-        // find first cell without any links
-        if graph.graph().node(node).is_empty() {
-            // choose a random cell that has links
-            if let Some(n) = graph.graph().linked_neighbors(node).choose(rng) {
-                graph.set_bg(node, Some(SEL_NODE));
-                let image = graph.render();
-                encoder.add_frame(&*image, *timestamp)?;
-                *timestamp += opts.frame_time();
-                graph.set_bg(node, Some(IN_PATH));
-
-                graph.set_bg(*n, Some(IN_PATH));
-                let image = graph.render();
-                encoder.add_frame(&*image, *timestamp)?;
-                *timestamp += opts.frame_time();
-
-                return graph.graph_mut().link(node, *n).map(|_| Some(*n));
-            }
+    let mut hunter = Hunter::new(graph.graph(), hunt_opts, rng);
+    let start = graph
+        .graph()
+        .random(rng)
+        .ok_or(crate::Error::ZeroSizedGraph())?;
+    let mut anim = Animation::new(graph, opts)?;
+    run(&mut anim, &mut hunter, start, rng)?;
+    anim.encode()
+}
+
+/// Walk from `start`, hunting for a new node each time the walk reaches a dead end
+fn run<C, R>(
+    canvas: &mut C,
+    hunter: &mut Hunter,
+    start: usize,
+    rng: &mut R,
+) -> Result<(), crate::Error>
+where
+    C: Canvas,
+    R: Rng + ?Sized,
+{
+    let mut node = start;
+    visit(canvas, node)?;
+    loop {
+        if let Some(n) = canvas.graph().unlinked_neighbors(node).choose(rng) {
+            let n = *n;
+            canvas.set_bg(n, Some(SEL_NODE));
+            canvas.frame()?;
+            canvas.set_bg(n, Some(IN_PATH));
+            canvas.graph_mut().link(node, n)?;
+            canvas.frame()?;
+            node = n;
+        } else if let Some(n) = hunt(canvas, hunter, node, rng)? {
+            visit(canvas, n)?;
+            node = n;
+        } else {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Highlight the node where a walk starts
+fn visit<C: Canvas>(canvas: &mut C, node: usize) -> Result<(), crate::Error> {
+    canvas.set_bg(node, Some(SEL_NODE));
+    canvas.frame()?;
+    canvas.set_bg(node, Some(IN_PATH));
+    canvas.frame()
+}
+
+fn hunt<C, R>(
+    canvas: &mut C,
+    hunter: &mut Hunter,
+    last: usize,
+    rng: &mut R,
+) -> Result<Option<usize>, crate::Error>
+where
+    C: Canvas,
+    R: Rng + ?Sized,
+{
+    if let Some(node) = hunter.find(canvas.graph(), last) {
+        // choose a random cell that has links
+        if let Some(n) = canvas.graph().linked_neighbors(node).choose(rng) {
+            let n = *n;
+            canvas.set_bg(node, Some(SEL_NODE));
+            canvas.frame()?;
+            canvas.set_bg(node, Some(IN_PATH));
+            canvas.set_bg(n, Some(IN_PATH));
+            canvas.frame()?;
+            return canvas.graph_mut().link(node, n).map(|_| Some(n));
         }
     }
     Ok(None)
@@ -142,9 +244,19 @@ where
 
 #[cfg(test)]
 mod tests {
+    use super::{HuntOpts, HuntOrder};
+    use crate::graphs::{Graph, Node};
     use crate::render::opts::AnimOpts;
     use rand::SeedableRng;
     use rand_xoshiro::SplitMix64;
+
+    fn links<G: Graph>(graph: &G) -> Vec<Vec<usize>> {
+        graph
+            .nodes()
+            .map(|node| node.links().copied().collect())
+            .collect()
+    }
+
     #[test]
     fn algo_huntkill() -> Result<(), image::ImageError> {
         crate::logger(crate::LOG_LEVEL);
@@ -173,4 +285,33 @@ mod tests {
         f.write_all(&bytes).expect("Error writing to file");
         Ok(())
     }
+
+    #[test]
+    fn hunt_opts() {
+        crate::logger(log::LevelFilter::Warn);
+        for order in [HuntOrder::RowScan, HuntOrder::Random, HuntOrder::Nearest] {
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            let mut graph = crate::Orth::new(10, 10);
+            super::huntkill_with(&mut graph, &HuntOpts::new(order), rng).unwrap();
+            assert!(graph.nodes().all(|node| !node.is_empty()));
+            let num_links: usize = graph.nodes().map(|node| node.num_links()).sum();
+            assert_eq!(num_links / 2, graph.len() - 1);
+
+            // resuming the scan only skips work, so the maze is the same
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            let mut resumed = crate::Orth::new(10, 10);
+            let opts = HuntOpts::new(order).resume(true);
+            super::huntkill_with(&mut resumed, &opts, rng).unwrap();
+            assert_eq!(links(&graph), links(&resumed));
+        }
+
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut plain = crate::Orth::new(6, 6);
+        let opts = HuntOpts::new(HuntOrder::Nearest);
+        super::huntkill_with(&mut plain, &opts, rng).unwrap();
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Orth::new(6, 6).build_render_owned().finish();
+        super::animated_huntkill_with(&mut graph, &opts, &AnimOpts::default(), rng).unwrap();
+        assert_eq!(links(&plain), links(graph.graph()));
+    }
 }
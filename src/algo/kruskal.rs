@@ -0,0 +1,151 @@
+use super::edge_weights;
+use crate::graphs::weight::Uniform;
+use crate::graphs::Graph;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Randomized Kruskal: link edges from cheapest to most expensive, skipping any edge that would
+/// create a loop.
+///
+/// Edges are weighted by `weights`, or at random when there are none.  Edges with the same weight
+/// are taken in random order.  Each connected component of the graph gets its own tree.  Edges the
+/// graph refuses to link when their turn comes (e.g., tunnels in a [`Weave`](crate::Weave) with nothing
+/// to pass under) are skipped.
+pub fn kruskal<G: Graph, R: Rng + ?Sized>(
+    graph: &mut G,
+    weights: Option<&Uniform>,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    let weights = edge_weights(graph, weights, rng)?;
+    let mut edges: Vec<(usize, usize, usize)> = weights
+        .iter()
+        .map(|e| (*e.value(), e.a().id(), e.b().id()))
+        .collect();
+    edges.shuffle(rng);
+    edges.sort_by_key(|(weight, _, _)| *weight);
+
+    let mut sets = DisjointSet::new(graph.len());
+    for (_, a, b) in edges {
+        if sets.find(a) == sets.find(b) {
+            continue;
+        }
+        match graph.link(a, b) {
+            Ok(()) => {
+                sets.union(a, b);
+            }
+            // the graph may refuse an edge in its current state (e.g., a tunnel in a Weave)
+            Err(crate::Error::InvalidNeighbor(_, _)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Union-find over node ids
+struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, mut id: usize) -> usize {
+        while self.parent[id] != id {
+            // path halving
+            self.parent[id] = self.parent[self.parent[id]];
+            id = self.parent[id];
+        }
+        id
+    }
+
+    /// Join the sets containing `a` and `b`.  Returns false if they were already in the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphs::weight::Uniform;
+    use crate::graphs::{Graph, Node};
+    use crate::{Orth, RectCell};
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn algo_kruskal() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(8, 8);
+        super::kruskal(&mut graph, None, rng).unwrap();
+        assert!(graph.nodes().all(|node| !node.is_empty()));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, graph.len() - 1);
+        graph.render().save("images/tests/rect_kruskal.png")
+    }
+
+    #[test]
+    fn kruskal_weighted() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph: Orth<RectCell> = Orth::new(6, 6);
+        // vertical edges are cheap, so every column is linked from top to bottom
+        let weights = Uniform::from_slice(&graph, &[[1, 100, 1, 100]; 36]).unwrap();
+        super::kruskal(&mut graph, Some(&weights), rng).unwrap();
+        for node in graph.nodes() {
+            for side in [0, 2] {
+                assert_eq!(node.neighbor(side).is_some(), node.linked_side(side));
+            }
+        }
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, graph.len() - 1);
+    }
+
+    #[test]
+    fn kruskal_mismatched_weights() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let small: Orth<RectCell> = Orth::new(4, 4);
+        let weights = Uniform::from_slice(&small, &[[1; 4]; 16]).unwrap();
+
+        let mut graph: Orth<RectCell> = Orth::new(6, 6);
+        let result = super::kruskal(&mut graph, Some(&weights), rng);
+        assert!(matches!(
+            result,
+            Err(crate::Error::MismatchedListSize(16, 36))
+        ));
+
+        // same number of nodes, but the edges don't line up
+        let mut graph: Orth<RectCell> = Orth::new(2, 8);
+        let result = super::kruskal(&mut graph, Some(&weights), rng);
+        assert!(matches!(result, Err(crate::Error::InvalidEdge(_, _))));
+        assert!(graph.nodes().all(|node| node.is_empty()));
+    }
+
+    #[test]
+    fn kruskal_weave() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Weave::new(8, 8);
+        super::kruskal(&mut graph, None, rng).unwrap();
+        let dist = graph.dist(0);
+        assert!(graph.node_ids().iter().all(|id| dist.dist(*id).is_some()));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, graph.len() - 1);
+    }
+}
@@ -74,28 +74,18 @@ fn shortest_path<G: Graph>(
     dist: &crate::algo::dist::Dist,
     end: usize,
 ) -> Result<crate::algo::path::Path, crate::error::Error> {
-    if let Some(d) = dist.dist(end) {
-        let mut path = Path::with_capacity(graph, d);
-        let mut cell = graph.node(end);
-
-        for _ in 0..=d {
-            path.add(cell.id())?;
-
-            let prev = cell
-                .links()
-                .reduce(|min, id| {
-                    if dist.dist(*id) < dist.dist(*min) {
-                        id
-                    } else {
-                        min
-                    }
-                })
-                .unwrap();
-            cell = graph.node(*prev);
-        }
-        path.reverse();
-        Ok(path)
-    } else {
-        Err(crate::error::Error::NoPathAvailable(end))
+    if dist.dist(end).is_none() {
+        return Err(crate::error::Error::NoPathAvailable(end));
+    }
+    // follow the predecessors back to the start, which also works for weighted distances
+    let mut steps = vec![end];
+    while let Some(prev) = dist.prev(*steps.last().unwrap()) {
+        steps.push(prev);
+    }
+    let mut path = Path::with_capacity(graph, steps.len() - 1);
+    for id in steps {
+        path.add(id)?;
     }
+    path.reverse();
+    Ok(path)
 }
@@ -0,0 +1,174 @@
+use super::{components, edge_weights};
+use crate::edges::Undirected;
+use crate::graphs::weight::Uniform;
+use crate::graphs::{Graph, Node};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Simplified Prim: grow the maze from a random node in the maze, linking it to its cheapest unvisited
+/// neighbor.
+///
+/// Edges are weighted by `weights`, or at random when there are none.  Produces lots of short
+/// dead ends radiating from the start.  Each connected component of the graph gets its own tree.
+pub fn simplified_prim<G: Graph, R: Rng + ?Sized>(
+    graph: &mut G,
+    weights: Option<&Uniform>,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    let weights = edge_weights(graph, weights, rng)?;
+    for group in components(graph) {
+        let mut active = vec![*group.choose(rng).expect("components are never empty")];
+        while !active.is_empty() {
+            let i = rng.gen_range(0..active.len());
+            let node = active[i];
+            let mut unvisited = graph.unlinked_neighbors(node);
+            unvisited.shuffle(rng);
+            let next = unvisited
+                .iter()
+                .map(|n| Ok((weight(graph, &weights, node, *n)?, *n)))
+                .collect::<Result<Vec<_>, crate::Error>>()?
+                .into_iter()
+                .min_by_key(|(weight, _)| *weight);
+            match next {
+                Some((_, n)) => {
+                    graph.link(node, n)?;
+                    active.push(n);
+                }
+                None => {
+                    active.swap_remove(i);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// True Prim: grow the maze by always linking the cheapest edge between the maze and an unvisited node.
+///
+/// Edges are weighted by `weights`, or at random when there are none.  Edges with the same weight
+/// are taken in random order.  Each connected component of the graph gets its own tree.  Edges the
+/// graph refuses to link when their turn comes (e.g., tunnels in a [`Weave`](crate::Weave) with
+/// nothing to pass under) are skipped.
+pub fn prim<G: Graph, R: Rng + ?Sized>(
+    graph: &mut G,
+    weights: Option<&Uniform>,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    let weights = edge_weights(graph, weights, rng)?;
+    let mut visited = vec![false; graph.len()];
+    for group in components(graph) {
+        let start = *group.choose(rng).expect("components are never empty");
+        visited[start] = true;
+        // (weight, tie breaker, from, to)
+        let mut queue = BinaryHeap::new();
+        push_edges(graph, &weights, &visited, start, &mut queue, rng)?;
+        while let Some(Reverse((_, _, from, to))) = queue.pop() {
+            if visited[to] {
+                continue;
+            }
+            match graph.link(from, to) {
+                Ok(()) => visited[to] = true,
+                // the graph may refuse an edge in its current state (e.g., a tunnel in a Weave)
+                Err(crate::Error::InvalidNeighbor(_, _)) => continue,
+                Err(e) => return Err(e),
+            }
+            push_edges(graph, &weights, &visited, to, &mut queue, rng)?;
+        }
+    }
+    Ok(())
+}
+
+type Queue = BinaryHeap<Reverse<(usize, u32, usize, usize)>>;
+
+/// Queue the edges from `node` to each of its unvisited neighbors
+fn push_edges<G: Graph, R: Rng + ?Sized>(
+    graph: &G,
+    weights: &Undirected<usize>,
+    visited: &[bool],
+    node: usize,
+    queue: &mut Queue,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    for (side, n) in graph.node(node).all_neighbors().iter().enumerate() {
+        if let Some(n) = n {
+            if !visited[*n] {
+                let weight = *weights.edge_value(node, side)?;
+                queue.push(Reverse((weight, rng.gen(), node, *n)));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn weight<G: Graph>(
+    graph: &G,
+    weights: &Undirected<usize>,
+    node: usize,
+    neighbor: usize,
+) -> Result<usize, crate::Error> {
+    match graph.node(node).neighbor_id(neighbor) {
+        Some(side) => weights.edge_value(node, side).copied(),
+        None => Err(crate::Error::InvalidNeighbor(node, neighbor)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphs::weight::Uniform;
+    use crate::graphs::{Graph, Node};
+    use crate::{Orth, RectCell};
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn algo_prim() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        type Algo =
+            fn(&mut Orth<RectCell>, Option<&Uniform>, &mut SplitMix64) -> Result<(), crate::Error>;
+        let algos: [(&str, Algo); 2] = [
+            ("simplified_prim", super::simplified_prim),
+            ("prim", super::prim),
+        ];
+        for (name, algo) in algos {
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            let mut graph = Orth::new(8, 8);
+            algo(&mut graph, None, rng).unwrap();
+            assert!(graph.nodes().all(|node| !node.is_empty()));
+            let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+            assert_eq!(links / 2, graph.len() - 1);
+            graph
+                .render()
+                .save(format!("images/tests/rect_{name}.png"))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn prim_weighted() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph: Orth<RectCell> = Orth::new(6, 6);
+        // horizontal edges are cheap, so every row is linked from left to right
+        let weights = Uniform::from_slice(&graph, &[[100, 1, 100, 1]; 36]).unwrap();
+        super::prim(&mut graph, Some(&weights), rng).unwrap();
+        for node in graph.nodes() {
+            for side in [1, 3] {
+                assert_eq!(node.neighbor(side).is_some(), node.linked_side(side));
+            }
+        }
+    }
+
+    #[test]
+    fn prim_weave() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Weave::new(8, 8);
+        super::prim(&mut graph, None, rng).unwrap();
+        let dist = graph.dist(0);
+        assert!(graph.node_ids().iter().all(|id| dist.dist(*id).is_some()));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, graph.len() - 1);
+    }
+}
@@ -0,0 +1,496 @@
+use crate::graphs::{Graph, Node};
+use crate::render::state::NodeState;
+use crate::{Orth, RectCell};
+use image::Rgba;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Options for [`dungeon`]
+#[derive(Clone, Debug)]
+pub struct RoomOpts {
+    attempts: usize,
+    min_height: usize,
+    min_width: usize,
+    max_height: usize,
+    max_width: usize,
+    doors: usize,
+}
+
+impl Default for RoomOpts {
+    fn default() -> Self {
+        Self {
+            attempts: 20,
+            min_height: 2,
+            min_width: 2,
+            max_height: 4,
+            max_width: 4,
+            doors: 1,
+        }
+    }
+}
+
+impl RoomOpts {
+    /// Number of random placements to try; placements that overlap another room are dropped
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Smallest room size
+    pub fn min_size(mut self, height: usize, width: usize) -> Self {
+        self.min_height = height.max(1);
+        self.min_width = width.max(1);
+        self
+    }
+
+    /// Largest room size
+    pub fn max_size(mut self, height: usize, width: usize) -> Self {
+        self.max_height = height.max(1);
+        self.max_width = width.max(1);
+        self
+    }
+
+    /// Number of doors linking each room to the maze around it
+    pub fn doors(mut self, doors: usize) -> Self {
+        self.doors = doors.max(1);
+        self
+    }
+
+    pub fn num_attempts(&self) -> usize {
+        self.attempts
+    }
+
+    pub fn min_height(&self) -> usize {
+        self.min_height
+    }
+
+    pub fn min_width(&self) -> usize {
+        self.min_width
+    }
+
+    pub fn max_height(&self) -> usize {
+        self.max_height.max(self.min_height)
+    }
+
+    pub fn max_width(&self) -> usize {
+        self.max_width.max(self.min_width)
+    }
+
+    pub fn num_doors(&self) -> usize {
+        self.doors
+    }
+}
+
+/// Rectangular region of an [`Orth`] graph
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Room {
+    row: usize,
+    col: usize,
+    height: usize,
+    width: usize,
+}
+
+impl Room {
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        (self.row..self.row + self.height).contains(&row)
+            && (self.col..self.col + self.width).contains(&col)
+    }
+
+    /// Node ids of the room in a graph `columns` wide
+    fn ids(&self, columns: usize) -> Vec<usize> {
+        (self.row..self.row + self.height)
+            .flat_map(|row| (self.col..self.col + self.width).map(move |col| row * columns + col))
+            .collect()
+    }
+
+    /// Whether the rooms overlap or touch, including diagonally
+    fn touches(&self, other: &Room) -> bool {
+        self.row <= other.row + other.height
+            && other.row <= self.row + self.height
+            && self.col <= other.col + other.width
+            && other.col <= self.col + self.width
+    }
+}
+
+/// Rooms placed by [`dungeon`]
+#[derive(Clone, Debug)]
+pub struct Rooms {
+    columns: usize,
+    rooms: Vec<Room>,
+    doors: Vec<Vec<(usize, usize)>>,
+    owner: Vec<Option<usize>>,
+}
+
+impl Rooms {
+    pub fn len(&self) -> usize {
+        self.rooms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rooms.is_empty()
+    }
+
+    pub fn get(&self, room: usize) -> Option<&Room> {
+        self.rooms.get(room)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Room> {
+        self.rooms.iter()
+    }
+
+    /// Index of the room a node is in, if any
+    pub fn room_of(&self, id: usize) -> Option<usize> {
+        self.owner.get(id).copied().flatten()
+    }
+
+    /// Node ids of a room, in id order
+    pub fn cells(&self, room: usize) -> Vec<usize> {
+        self.rooms
+            .get(room)
+            .map_or_else(Vec::new, |r| r.ids(self.columns))
+    }
+
+    /// Doors of a room, as pairs of a node inside the room and the node outside it
+    pub fn doors(&self, room: usize) -> &[(usize, usize)] {
+        self.doors.get(room).map_or(&[], |doors| doors.as_slice())
+    }
+
+    /// Give every node inside a room a background of `color`.
+    ///
+    /// ```ignore
+    /// let mut nodes = NodeState::vec(graph.len(), GraphOpts::default().colors().cell_bg());
+    /// rooms.paint(&mut nodes, ROOM_BG);
+    /// let image = graph.build_render().default_opts().default_blocks().owned_nodes(nodes).finish().render();
+    /// ```
+    pub fn paint(&self, nodes: &mut [NodeState], color: Rgba<u8>) {
+        for (id, node) in nodes.iter_mut().enumerate() {
+            if self.room_of(id).is_some() {
+                node.set(Some(color));
+            }
+        }
+    }
+}
+
+/// Dungeon: places non-overlapping rooms, then fills the rest of the graph with a maze.
+///
+/// Rooms are fully linked inside, never touch each other, and never span the full height or width
+/// of the graph, so the space around them stays connected unless a mask splits it.  Each room gets up to [`RoomOpts::doors`] doors into the
+/// maze; more than one door per room adds loops.  Rooms are placed as if the graph did not wrap.
+///
+/// Returns an error without changing the graph if any node already has links; use [`carve`] to add
+/// rooms to an existing maze.
+pub fn dungeon<R: Rng + ?Sized>(
+    graph: &mut Orth<RectCell>,
+    opts: &RoomOpts,
+    rng: &mut R,
+) -> Result<Rooms, crate::Error> {
+    if let Some(node) = graph.nodes().find(|node| !node.is_empty()) {
+        return Err(crate::Error::LinkedGraph(node.id()));
+    }
+    let rooms = place(graph, opts, rng)?;
+    corridors(graph, &rooms, rng)?;
+    doors(graph, rooms, opts.doors, rng)
+}
+
+/// Carve rooms into an existing maze by linking everything inside each room.
+///
+/// Rooms are placed like in [`dungeon`].  Every link of the maze that crosses the wall of a room is
+/// kept as one of its doors, so [`RoomOpts::doors`] is not used: removing a door could cut the maze
+/// in two.  In a connected maze every room gets at least one door.
+pub fn carve<R: Rng + ?Sized>(
+    graph: &mut Orth<RectCell>,
+    opts: &RoomOpts,
+    rng: &mut R,
+) -> Result<Rooms, crate::Error> {
+    let mut rooms = place(graph, opts, rng)?;
+    for room in 0..rooms.len() {
+        rooms.doors[room] = rooms
+            .cells(room)
+            .into_iter()
+            .flat_map(|id| {
+                graph
+                    .node(id)
+                    .links()
+                    .filter(|n| rooms.room_of(**n) != Some(room))
+                    .map(move |n| (id, *n))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+    }
+    Ok(rooms)
+}
+
+/// Pick room positions and link each room internally
+fn place<R: Rng + ?Sized>(
+    graph: &mut Orth<RectCell>,
+    opts: &RoomOpts,
+    rng: &mut R,
+) -> Result<Rooms, crate::Error> {
+    let (height, width) = (graph.height(), graph.width());
+    let mut rooms = Rooms {
+        columns: width,
+        rooms: Vec::new(),
+        doors: Vec::new(),
+        owner: vec![None; graph.len()],
+    };
+    // a room as tall or as wide as the graph would cut the corridors in two
+    if height <= opts.min_height || width <= opts.min_width {
+        return Ok(rooms);
+    }
+
+    for _ in 0..opts.attempts {
+        let h = rng.gen_range(opts.min_height..=opts.max_height().min(height - 1));
+        let w = rng.gen_range(opts.min_width..=opts.max_width().min(width - 1));
+        let room = Room {
+            row: rng.gen_range(0..=height - h),
+            col: rng.gen_range(0..=width - w),
+            height: h,
+            width: w,
+        };
+        if rooms.rooms.iter().any(|other| other.touches(&room)) {
+            continue;
+        }
+        let cells = room.ids(width);
+        if !cells.iter().all(|id| graph.enabled(*id)) {
+            continue;
+        }
+        let index = rooms.rooms.len();
+        rooms.rooms.push(room);
+        for &id in &cells {
+            rooms.owner[id] = Some(index);
+        }
+        for &id in &cells {
+            // link east and south to cover each edge once
+            for side in [1, 2] {
+                if let Some(n) = graph.node(id).neighbor(side) {
+                    if rooms.owner[n] == Some(index) && !graph.node(id).linked_to(n) {
+                        graph.link(id, n)?;
+                    }
+                }
+            }
+        }
+    }
+    rooms.doors = vec![Vec::new(); rooms.rooms.len()];
+    Ok(rooms)
+}
+
+/// Recursive backtracker over the nodes outside of rooms
+fn corridors<R: Rng + ?Sized>(
+    graph: &mut Orth<RectCell>,
+    rooms: &Rooms,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    let mut visited: Vec<bool> = (0..graph.len())
+        .map(|id| rooms.room_of(id).is_some())
+        .collect();
+    let mut open: Vec<usize> = graph
+        .node_ids()
+        .into_iter()
+        .filter(|id| !visited[*id])
+        .collect();
+    open.shuffle(rng);
+
+    for start in open {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![start];
+        while let Some(&node) = stack.last() {
+            let options: Vec<usize> = graph
+                .node(node)
+                .neighbors()
+                .filter(|n| !visited[**n])
+                .copied()
+                .collect();
+            match options.choose(rng) {
+                Some(&n) => {
+                    graph.link(node, n)?;
+                    visited[n] = true;
+                    stack.push(n);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Link each room to the nodes around it
+fn doors<R: Rng + ?Sized>(
+    graph: &mut Orth<RectCell>,
+    mut rooms: Rooms,
+    doors: usize,
+    rng: &mut R,
+) -> Result<Rooms, crate::Error> {
+    for room in 0..rooms.len() {
+        let mut edges: Vec<(usize, usize)> = rooms
+            .cells(room)
+            .into_iter()
+            .flat_map(|id| {
+                graph
+                    .node(id)
+                    .neighbors()
+                    .filter(|n| rooms.room_of(**n).is_none())
+                    .map(move |n| (id, *n))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        edges.shuffle(rng);
+        edges.truncate(doors);
+        for &(id, n) in &edges {
+            graph.link(id, n)?;
+        }
+        rooms.doors[room] = edges;
+    }
+    Ok(rooms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::opts::GraphOpts;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn algo_dungeon() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(16, 16);
+        let opts = RoomOpts::default().attempts(30).doors(2);
+        let rooms = dungeon(&mut graph, &opts, rng).unwrap();
+        assert!(rooms.len() > 1);
+
+        for (i, room) in rooms.iter().enumerate() {
+            let cells = rooms.cells(i);
+            assert_eq!(cells.len(), room.height() * room.width());
+            assert!(cells.iter().all(|id| rooms.room_of(*id) == Some(i)));
+            assert!(cells
+                .iter()
+                .all(|id| room.contains(id / graph.width(), id % graph.width())));
+
+            // fully linked inside, and only linked outside through doors
+            let links: Vec<Option<usize>> = cells
+                .iter()
+                .flat_map(|id| graph.node(*id).links().map(|n| rooms.room_of(*n)))
+                .collect();
+            let inside = links.iter().filter(|r| **r == Some(i)).count();
+            let outside = links.len() - inside;
+            let (h, w) = (room.height(), room.width());
+            assert_eq!(inside / 2, h * (w - 1) + w * (h - 1));
+            assert_eq!(outside, rooms.doors(i).len());
+            assert_eq!(outside, 2);
+        }
+
+        // every node can be reached
+        let dist = graph.dist(0);
+        assert!(graph.node_ids().iter().all(|id| dist.dist(*id).is_some()));
+
+        // placement is seeded
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut other = Orth::new(16, 16);
+        let again = dungeon(&mut other, &opts, rng).unwrap();
+        assert!(rooms.iter().eq(again.iter()));
+
+        let mut nodes = NodeState::vec(graph.len(), GraphOpts::default().colors().cell_bg());
+        rooms.paint(&mut nodes, crate::color::YELLOW);
+        graph
+            .build_render()
+            .default_opts()
+            .default_blocks()
+            .owned_nodes(nodes)
+            .finish()
+            .render()
+            .save("images/tests/rect_dungeon.png")
+    }
+
+    #[test]
+    fn dungeon_connected() {
+        crate::logger(log::LevelFilter::Warn);
+        for seed in 0..20 {
+            let rng = &mut SplitMix64::seed_from_u64(seed);
+            let mut graph = Orth::new(4, 16);
+            let rooms = dungeon(&mut graph, &RoomOpts::default(), rng).unwrap();
+            assert!(rooms.iter().all(|room| room.height() < 4));
+            let dist = graph.dist(0);
+            assert!(graph.node_ids().iter().all(|id| dist.dist(*id).is_some()));
+        }
+
+        // the graph must start without links
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(8, 8);
+        graph.link(9, 10).unwrap();
+        assert!(matches!(
+            dungeon(&mut graph, &RoomOpts::default(), rng),
+            Err(crate::Error::LinkedGraph(9))
+        ));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links, 2);
+
+        // rooms can't fit without spanning the graph
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(2, 16);
+        assert!(dungeon(&mut graph, &RoomOpts::default(), rng)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn carve_maze() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(12, 12);
+        crate::algo::backtrack::backtracker(&mut graph, rng).unwrap();
+        let maze = graph.clone();
+        let rooms = carve(&mut graph, &RoomOpts::default().attempts(20), rng).unwrap();
+        assert!(!rooms.is_empty());
+
+        for (i, room) in rooms.iter().enumerate() {
+            let cells = rooms.cells(i);
+            let inside: usize = cells
+                .iter()
+                .map(|id| {
+                    graph
+                        .node(*id)
+                        .links()
+                        .filter(|n| rooms.room_of(**n) == Some(i))
+                        .count()
+                })
+                .sum();
+            let (h, w) = (room.height(), room.width());
+            assert_eq!(inside / 2, h * (w - 1) + w * (h - 1));
+            assert!(!rooms.doors(i).is_empty());
+            // doors are the links the maze already had
+            for (id, n) in rooms.doors(i) {
+                assert!(maze.node(*id).linked_to(*n));
+            }
+        }
+        // links outside of the rooms are untouched
+        for id in graph.node_ids() {
+            if rooms.room_of(id).is_none() {
+                assert!(graph.node(id).links().eq(maze.node(id).links()));
+            }
+        }
+        let dist = graph.dist(0);
+        assert!(graph.node_ids().iter().all(|id| dist.dist(*id).is_some()));
+    }
+}
@@ -0,0 +1,141 @@
+use super::binary::Bias;
+use super::{Animation, Canvas, Plain, IN_PATH, SEL_NODE};
+use crate::graphs::{Graph, Node};
+use crate::render::state::graph;
+use crate::{Orth, RectCell};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use webp_animation::WebPData;
+
+/// Sidewinder: carves runs of cells in the horizontal direction of `bias`, closing each run with a
+/// probability of `close` by linking one of its cells in the vertical direction.
+///
+/// Works one row at a time.  Lower values of `close` give longer horizontal passages.
+pub fn sidewinder<R: Rng + ?Sized>(
+    graph: &mut Orth<RectCell>,
+    bias: Bias,
+    close: f64,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    run(&mut Plain(graph), bias, close, rng)
+}
+
+/// Animated version of [`sidewinder`], highlighting the current run
+pub fn animated_sidewinder<R: Rng + ?Sized>(
+    graph: &mut graph::State<Orth<RectCell>>,
+    bias: Bias,
+    close: f64,
+    opts: &crate::render::opts::AnimOpts,
+    rng: &mut R,
+) -> Result<WebPData, crate::Error> {
+    if graph.graph().node_ids().is_empty() {
+        return Err(crate::Error::ZeroSizedGraph());
+    }
+    let mut anim = Animation::new(graph, opts)?;
+    run(&mut anim, bias, close, rng)?;
+    anim.finish()
+}
+
+fn run<C, R>(canvas: &mut C, bias: Bias, close: f64, rng: &mut R) -> Result<(), crate::Error>
+where
+    C: Canvas<Graph = Orth<RectCell>>,
+    R: Rng + ?Sized,
+{
+    let close = close.clamp(0.0, 1.0);
+    let (height, width) = (canvas.graph().height(), canvas.graph().width());
+    for row in 0..height {
+        // walk each row towards the horizontal direction
+        let cols: Vec<usize> = match bias.horizontal() {
+            1 => (0..width).collect(),
+            _ => (0..width).rev().collect(),
+        };
+        let mut run: Vec<usize> = Vec::new();
+        for col in cols {
+            let id = row * width + col;
+            let node = canvas.graph().node(id);
+            let (vertical, horizontal) = (
+                node.neighbor(bias.vertical()),
+                node.neighbor(bias.horizontal()),
+            );
+            run.push(id);
+            canvas.set_bg(id, Some(SEL_NODE));
+
+            let at_vertical_edge = bias.at_vertical_edge(canvas.graph(), id) || vertical.is_none();
+            let at_horizontal_edge =
+                bias.at_horizontal_edge(canvas.graph(), id) || horizontal.is_none();
+            let close_run = at_horizontal_edge || (!at_vertical_edge && rng.gen_bool(close));
+
+            if close_run {
+                let member = *run.choose(rng).expect("runs are never empty");
+                if !bias.at_vertical_edge(canvas.graph(), member) {
+                    if let Some(n) = canvas.graph().node(member).neighbor(bias.vertical()) {
+                        canvas.graph_mut().link(member, n)?;
+                    }
+                }
+                canvas.frame()?;
+                for id in run.drain(..) {
+                    canvas.set_bg(id, None);
+                }
+            } else if let Some(n) = horizontal {
+                canvas.graph_mut().link(id, n)?;
+                canvas.frame()?;
+                canvas.set_bg(id, Some(IN_PATH));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::opts::AnimOpts;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn algo_sidewinder() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        for bias in [Bias::NE, Bias::NW, Bias::SE, Bias::SW] {
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            let mut graph = Orth::new(8, 8);
+            sidewinder(&mut graph, bias, 0.5, rng).unwrap();
+            assert!(graph.nodes().all(|node| !node.is_empty()));
+            let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+            assert_eq!(links / 2, graph.len() - 1);
+        }
+
+        // never closing runs early links every row from end to end
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(8, 8);
+        sidewinder(&mut graph, Bias::SW, 0.0, rng).unwrap();
+        for id in graph.node_ids() {
+            assert_eq!(graph.node(id).linked_side(1), id % 8 != 7);
+        }
+
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(8, 8);
+        sidewinder(&mut graph, Bias::NE, 0.5, rng).unwrap();
+        graph.render().save("images/tests/rect_sidewinder.png")
+    }
+
+    #[test]
+    fn anim_sidewinder() -> Result<(), crate::Error> {
+        use std::fs::OpenOptions;
+        use std::io::prelude::*;
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = Orth::new(6, 6).build_render_owned().finish();
+        let bytes = animated_sidewinder(&mut graph, Bias::NE, 0.5, &AnimOpts::default(), rng)?;
+        assert!(graph.node_state().iter().all(|state| state.get().is_none()));
+
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open("images/tests/rect_sidewinder.webp")
+            .unwrap();
+        f.write_all(&bytes).expect("Error writing to file");
+        Ok(())
+    }
+}
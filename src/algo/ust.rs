@@ -0,0 +1,325 @@
+use super::{components, Animation, Canvas, Plain, IN_PATH, SEL_NODE};
+use crate::graphs::{Graph, Node};
+use crate::render::state::graph;
+use crate::render::RenderGraph;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::Rng;
+use webp_animation::WebPData;
+
+/// Fraction of each component that [`hybrid`] visits with Aldous-Broder before switching to Wilson's algorithm
+const HYBRID_SWITCH: f64 = 1.0 / 3.0;
+
+/// Aldous-Broder: a random walk that links each node the first time it is reached.
+///
+/// Every spanning tree is equally likely.  The walk is quick to start but slow to find the last few
+/// unvisited nodes.
+///
+/// Each connected component of the graph gets its own tree.  The walk still moves along links the
+/// graph refuses (e.g., tunnels in a [`Weave`](crate::Weave)), but only links nodes from the tree.
+pub fn aldous_broder<G: Graph, R: Rng + ?Sized>(
+    graph: &mut G,
+    rng: &mut R,
+) -> Result<(), crate::Error> {
+    run(&mut Plain(graph), 1.0, rng)
+}
+
+/// Wilson's algorithm: loop-erased random walks from each unvisited node until they reach the tree.
+///
+/// Every spanning tree is equally likely.  The first walk is slow, but later walks get quicker as the
+/// tree grows.
+///
+/// Each connected component of the graph gets its own tree.  Nodes must be able to link to any
+/// unvisited neighbor, so graphs like [`Weave`](crate::Weave) should use [`aldous_broder`] instead.
+pub fn wilson<G: Graph, R: Rng + ?Sized>(graph: &mut G, rng: &mut R) -> Result<(), crate::Error> {
+    run(&mut Plain(graph), 0.0, rng)
+}
+
+/// Aldous-Broder until a third of the graph is visited, then Wilson's algorithm for the rest.
+///
+/// Every spanning tree is still equally likely, while avoiding the slow parts of both algorithms.
+pub fn hybrid<G: Graph, R: Rng + ?Sized>(graph: &mut G, rng: &mut R) -> Result<(), crate::Error> {
+    run(&mut Plain(graph), HYBRID_SWITCH, rng)
+}
+
+/// Animated version of [`aldous_broder`], showing the random walk
+pub fn animated_aldous_broder<G, R>(
+    graph: &mut graph::State<G>,
+    opts: &crate::render::opts::AnimOpts,
+    rng: &mut R,
+) -> Result<WebPData, crate::Error>
+where
+    R: Rng + ?Sized,
+    G: Graph + RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    animated(graph, opts, 1.0, rng)
+}
+
+/// Animated version of [`wilson`], showing each walk and its loops being erased
+pub fn animated_wilson<G, R>(
+    graph: &mut graph::State<G>,
+    opts: &crate::render::opts::AnimOpts,
+    rng: &mut R,
+) -> Result<WebPData, crate::Error>
+where
+    R: Rng + ?Sized,
+    G: Graph + RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    animated(graph, opts, 0.0, rng)
+}
+
+/// Animated version of [`hybrid`]
+pub fn animated_hybrid<G, R>(
+    graph: &mut graph::State<G>,
+    opts: &crate::render::opts::AnimOpts,
+    rng: &mut R,
+) -> Result<WebPData, crate::Error>
+where
+    R: Rng + ?Sized,
+    G: Graph + RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    animated(graph, opts, HYBRID_SWITCH, rng)
+}
+
+fn animated<G, R>(
+    graph: &mut graph::State<G>,
+    opts: &crate::render::opts::AnimOpts,
+    switch: f64,
+    rng: &mut R,
+) -> Result<WebPData, crate::Error>
+where
+    R: Rng + ?Sized,
+    G: Graph + RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    if graph.graph().node_ids().is_empty() {
+        return Err(crate::Error::ZeroSizedGraph());
+    }
+    let mut anim = Animation::new(graph, opts)?;
+    run(&mut anim, switch, rng)?;
+    anim.finish()
+}
+
+/// Build a spanning tree of each component, using Aldous-Broder until `switch` of the component
+/// has been visited and Wilson's algorithm after that
+fn run<C, R>(canvas: &mut C, switch: f64, rng: &mut R) -> Result<(), crate::Error>
+where
+    C: Canvas,
+    R: Rng + ?Sized,
+{
+    let len = canvas.graph().len();
+    let mut in_tree = vec![false; len];
+    let mut on_walk = vec![None; len];
+    for group in components(canvas.graph()) {
+        let root = *group.choose(rng).expect("components are never empty");
+        in_tree[root] = true;
+        let target = (group.len() as f64 * switch).ceil() as usize;
+        if target > 1 {
+            aldous_broder_walk(canvas, &mut in_tree, root, target, rng)?;
+        }
+        for &start in &group {
+            if !in_tree[start] {
+                wilson_walk(canvas, &mut in_tree, &mut on_walk, start, rng)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Random walk from `start` that links each unvisited node it reaches, until `target` nodes are in the tree
+fn aldous_broder_walk<C, R>(
+    canvas: &mut C,
+    in_tree: &mut [bool],
+    start: usize,
+    target: usize,
+    rng: &mut R,
+) -> Result<(), crate::Error>
+where
+    C: Canvas,
+    R: Rng + ?Sized,
+{
+    let mut node = start;
+    let mut visited = 1;
+    canvas.set_bg(node, Some(SEL_NODE));
+    canvas.frame()?;
+    while visited < target {
+        let n = match canvas.graph().node(node).neighbors().choose(rng) {
+            Some(n) => *n,
+            None => break,
+        };
+        // only link from the tree, as the walk may have left it through a refused link
+        if in_tree[node] && !in_tree[n] {
+            match canvas.graph_mut().link(node, n) {
+                Ok(()) => {
+                    in_tree[n] = true;
+                    visited += 1;
+                }
+                // the graph refused the link (e.g., a tunnel in a Weave), so just move
+                Err(crate::Error::InvalidNeighbor(_, _)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        canvas.set_bg(node, None);
+        canvas.set_bg(n, Some(SEL_NODE));
+        canvas.frame()?;
+        node = n;
+    }
+    canvas.set_bg(node, None);
+    Ok(())
+}
+
+/// Loop-erased random walk from `start` until it reaches the tree, then add the walk to the tree.
+///
+/// `on_walk` holds the position of each node in the current walk, and is left empty afterwards.
+fn wilson_walk<C, R>(
+    canvas: &mut C,
+    in_tree: &mut [bool],
+    on_walk: &mut [Option<usize>],
+    start: usize,
+    rng: &mut R,
+) -> Result<(), crate::Error>
+where
+    C: Canvas,
+    R: Rng + ?Sized,
+{
+    let mut walk = vec![start];
+    on_walk[start] = Some(0);
+    canvas.set_bg(start, Some(SEL_NODE));
+    canvas.frame()?;
+
+    let mut node = start;
+    while !in_tree[node] {
+        let n = match canvas.graph().node(node).neighbors().choose(rng) {
+            Some(n) => *n,
+            None => break,
+        };
+        canvas.set_bg(node, Some(IN_PATH));
+        if let Some(i) = on_walk[n] {
+            // erase the loop
+            for erased in walk.drain(i + 1..) {
+                on_walk[erased] = None;
+                canvas.set_bg(erased, None);
+            }
+        } else {
+            on_walk[n] = Some(walk.len());
+            walk.push(n);
+        }
+        canvas.set_bg(n, Some(SEL_NODE));
+        canvas.frame()?;
+        node = n;
+    }
+
+    for pair in walk.windows(2) {
+        canvas.graph_mut().link(pair[0], pair[1])?;
+    }
+    for node in walk {
+        in_tree[node] = true;
+        on_walk[node] = None;
+        canvas.set_bg(node, None);
+    }
+    canvas.frame()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphs::{Graph, Node};
+    use crate::render::opts::AnimOpts;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    /// Every node is reached and there are no loops
+    fn assert_spanning_tree<G: Graph>(graph: &G) {
+        assert!(graph.nodes().all(|node| !node.is_empty()));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, graph.len() - 1);
+    }
+
+    fn links<G: Graph>(graph: &G) -> Vec<Vec<usize>> {
+        graph
+            .nodes()
+            .map(|node| node.links().copied().collect())
+            .collect()
+    }
+
+    #[test]
+    fn algo_ust() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        type Algo =
+            fn(&mut crate::Orth<crate::RectCell>, &mut SplitMix64) -> Result<(), crate::Error>;
+        let algos: [(&str, Algo); 3] = [
+            ("aldous_broder", super::aldous_broder),
+            ("wilson", super::wilson),
+            ("hybrid", super::hybrid),
+        ];
+        for (name, algo) in algos {
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            let mut graph = crate::Orth::new(8, 8);
+            algo(&mut graph, rng).unwrap();
+            assert_spanning_tree(&graph);
+
+            // same seed, same maze
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            let mut again = crate::Orth::new(8, 8);
+            algo(&mut again, rng).unwrap();
+            assert_eq!(links(&graph), links(&again));
+
+            graph
+                .render()
+                .save(format!("images/tests/rect_{name}.png"))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ust_disconnected() {
+        crate::logger(log::LevelFilter::Warn);
+        let mask = crate::Mask::from_ascii("...X...\n...X...\n...X...").unwrap();
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Orth::masked(mask);
+        super::wilson(&mut graph, rng).unwrap();
+        // two separate trees of 9 nodes
+        assert!(graph.nodes().all(|node| !node.is_empty()));
+        let links: usize = graph.nodes().map(|node| node.num_links()).sum();
+        assert_eq!(links / 2, 16);
+    }
+
+    #[test]
+    fn ust_weave() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Weave::new(8, 8);
+        super::aldous_broder(&mut graph, rng).unwrap();
+        assert_spanning_tree(&graph);
+        let dist = graph.dist(0);
+        assert!(graph.node_ids().iter().all(|id| dist.dist(*id).is_some()));
+    }
+
+    #[test]
+    fn anim_wilson() -> Result<(), crate::Error> {
+        use std::fs::OpenOptions;
+        use std::io::prelude::*;
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = crate::Orth::new(6, 6).build_render_owned().finish();
+        let opts = AnimOpts::default();
+        let bytes = super::animated_wilson(&mut graph, &opts, rng)?;
+
+        // the animation generates the same maze as the plain version
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut plain = crate::Orth::new(6, 6);
+        super::wilson(&mut plain, rng)?;
+        assert_eq!(links(graph.graph()), links(&plain));
+        assert!(graph.node_state().iter().all(|state| state.get().is_none()));
+
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open("images/tests/rect_wilson.webp")
+            .unwrap();
+        f.write_all(&bytes).expect("Error writing to file");
+        Ok(())
+    }
+}
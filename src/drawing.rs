@@ -1,4 +1,7 @@
 pub(crate) mod cir;
+pub(crate) mod poly;
+
+use image::{Rgba, RgbaImage};
 
 #[derive(Clone, Debug)]
 pub struct Pt<T> {
@@ -122,3 +125,57 @@ where
         }
     }
 }
+
+/// Set a pixel, ignoring points that fall outside the image
+pub(crate) fn plot(image: &mut RgbaImage, pt: Pt<i32>, color: Rgba<u8>) {
+    if pt.x >= 0 && pt.y >= 0 && (pt.x as u32) < image.width() && (pt.y as u32) < image.height() {
+        image.put_pixel(pt.x as u32, pt.y as u32, color);
+    }
+}
+
+/// Blend a pixel with the existing color using the specified opacity (0.0 to 1.0)
+pub(crate) fn plot_alpha(image: &mut RgbaImage, pt: Pt<i32>, opacity: f32, color: Rgba<u8>) {
+    if pt.x >= 0 && pt.y >= 0 && (pt.x as u32) < image.width() && (pt.y as u32) < image.height() {
+        let bg = image.get_pixel(pt.x as u32, pt.y as u32);
+        let mix = |a: u8, b: u8| -> u8 { (a as f32 * (1.0 - opacity) + b as f32 * opacity) as u8 };
+        let px = Rgba([
+            mix(bg[0], color[0]),
+            mix(bg[1], color[1]),
+            mix(bg[2], color[2]),
+            bg[3].max(color[3]),
+        ]);
+        image.put_pixel(pt.x as u32, pt.y as u32, px);
+    }
+}
+
+/// Pixels along a straight line from `a` to `b`
+pub(crate) fn line_points(a: Pt<f64>, b: Pt<f64>) -> Vec<Pt<i32>> {
+    let dx = b.x - a.x;
+    let dy = b.y - a.y;
+    let steps = dx.abs().max(dy.abs()).ceil().max(1.0) as usize;
+    let mut pts: Vec<Pt<i32>> = Vec::with_capacity(steps + 1);
+    for i in 0..=steps {
+        let t = i as f64 / steps as f64;
+        let pt = Pt::new(a.x + dx * t, a.y + dy * t).i32();
+        match pts.last() {
+            Some(last) if last.x == pt.x && last.y == pt.y => {}
+            _ => pts.push(pt),
+        }
+    }
+    pts
+}
+
+/// Draw a series of pixels as a dashed line, alternating `width` pixels on and `width` pixels off
+pub(crate) fn draw_dashed(
+    pts: &[Pt<i32>],
+    width: u32,
+    opacity: f32,
+    image: &mut RgbaImage,
+    color: Rgba<u8>,
+) {
+    let width = width.max(1) as usize;
+    pts.iter()
+        .enumerate()
+        .filter(|(i, _)| (i / width) % 2 == 0)
+        .for_each(|(_, pt)| plot_alpha(image, *pt, opacity, color));
+}
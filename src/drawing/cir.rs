@@ -1,3 +1,7 @@
+use super::Pt;
+use image::{Rgba, RgbaImage};
+use std::f64::consts::PI;
+
 #[derive(Clone, Debug)]
 pub(crate) struct AnnulusSlice {
     center: (f64, f64),
@@ -74,12 +78,187 @@ impl AnnulusSlice {
         let y = (ay + by + cy + dy) / 4.0;
         (x, y)
     }
+
+    /// The point the torus is centered around
+    pub(crate) fn origin(&self) -> (f64, f64) {
+        self.center
+    }
+    pub(crate) fn inner(&self) -> f64 {
+        self.inner
+    }
+    pub(crate) fn outer(&self) -> f64 {
+        self.outer
+    }
+
+    /// Midpoint of the inner arc
+    pub(crate) fn mid_inner(&self) -> Pt<f64> {
+        Pt::from_radian((self.ccw + self.cw) / 2.0, self.inner, self.center)
+    }
+    /// Midpoint of the `i`th of `m` equal sections of the outer arc, counting from the clockwise side
+    pub(crate) fn mid_outer(&self, i: usize, m: usize) -> Pt<f64> {
+        let step = (self.ccw - self.cw) / m as f64;
+        Pt::from_radian(self.cw + step * (i as f64 + 0.5), self.outer, self.center)
+    }
+    /// Midpoint of the clockwise radial line
+    pub(crate) fn mid_cw(&self) -> Pt<f64> {
+        Pt::from_radian(self.cw, (self.inner + self.outer) / 2.0, self.center)
+    }
+    /// Midpoint of the counter-clockwise radial line
+    pub(crate) fn mid_ccw(&self) -> Pt<f64> {
+        Pt::from_radian(self.ccw, (self.inner + self.outer) / 2.0, self.center)
+    }
+
+    /// Whether a point falls inside the slice
+    pub(crate) fn contains(&self, x: f64, y: f64) -> bool {
+        let dx = x - self.cx();
+        let dy = self.cy() - y;
+        let r = (dx * dx + dy * dy).sqrt();
+        if r < self.inner || r > self.outer {
+            return false;
+        }
+        if self.ccw - self.cw >= PI * 2.0 {
+            return true;
+        }
+        let mut theta = dy.atan2(dx);
+        if theta < 0.0 {
+            theta += PI * 2.0;
+        }
+        (self.cw..=self.ccw).contains(&theta)
+    }
+
+    pub(crate) fn fill(&self, image: &mut RgbaImage, color: Rgba<u8>) {
+        let x0 = (self.cx() - self.outer).floor().max(0.0) as u32;
+        let y0 = (self.cy() - self.outer).floor().max(0.0) as u32;
+        let x1 = ((self.cx() + self.outer).ceil() as u32).min(image.width().saturating_sub(1));
+        let y1 = ((self.cy() + self.outer).ceil() as u32).min(image.height().saturating_sub(1));
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                if self.contains(x as f64, y as f64) {
+                    image.put_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn draw_inner(&self, image: &mut RgbaImage, color: Rgba<u8>) {
+        draw_arc(image, self.center, self.inner, self.cw, self.ccw, color);
+    }
+
+    /// Draw the `i`th of `m` equal sections of the outer arc, counting from the clockwise side
+    pub(crate) fn draw_outer(&self, i: usize, m: usize, image: &mut RgbaImage, color: Rgba<u8>) {
+        let (start, end) = self.outer_section(i, m);
+        draw_arc(image, self.center, self.outer, start, end, color);
+    }
+
+    pub(crate) fn draw_cw(&self, image: &mut RgbaImage, color: Rgba<u8>) {
+        let (a, b) = (self.c(), self.d());
+        imageproc::drawing::draw_line_segment_mut(
+            image,
+            (a.0 as f32, a.1 as f32),
+            (b.0 as f32, b.1 as f32),
+            color,
+        );
+    }
+
+    pub(crate) fn draw_ccw(&self, image: &mut RgbaImage, color: Rgba<u8>) {
+        let (a, b) = (self.a(), self.b());
+        imageproc::drawing::draw_line_segment_mut(
+            image,
+            (a.0 as f32, a.1 as f32),
+            (b.0 as f32, b.1 as f32),
+            color,
+        );
+    }
+
+    pub(crate) fn draw_dashed_inner(
+        &self,
+        width: u32,
+        opacity: f32,
+        image: &mut RgbaImage,
+        color: Rgba<u8>,
+    ) {
+        let pts = arc_points(self.center, self.inner, self.cw, self.ccw);
+        super::draw_dashed(&pts, width, opacity, image, color);
+    }
+
+    pub(crate) fn draw_dashed_outer(
+        &self,
+        i: usize,
+        m: usize,
+        width: u32,
+        opacity: f32,
+        image: &mut RgbaImage,
+        color: Rgba<u8>,
+    ) {
+        let (start, end) = self.outer_section(i, m);
+        let pts = arc_points(self.center, self.outer, start, end);
+        super::draw_dashed(&pts, width, opacity, image, color);
+    }
+
+    pub(crate) fn draw_dashed_cw(
+        &self,
+        width: u32,
+        opacity: f32,
+        image: &mut RgbaImage,
+        color: Rgba<u8>,
+    ) {
+        let pts = super::line_points(self.c().into(), self.d().into());
+        super::draw_dashed(&pts, width, opacity, image, color);
+    }
+
+    pub(crate) fn draw_dashed_ccw(
+        &self,
+        width: u32,
+        opacity: f32,
+        image: &mut RgbaImage,
+        color: Rgba<u8>,
+    ) {
+        let pts = super::line_points(self.a().into(), self.b().into());
+        super::draw_dashed(&pts, width, opacity, image, color);
+    }
+
+    /// Start and end angles of the `i`th of `m` equal sections of the outer arc
+    fn outer_section(&self, i: usize, m: usize) -> (f64, f64) {
+        let step = (self.ccw - self.cw) / m as f64;
+        (self.cw + step * i as f64, self.cw + step * (i + 1) as f64)
+    }
+}
+
+/// Pixels along an arc, ordered from `start` to `end` (counter-clockwise angles in radians)
+pub(crate) fn arc_points(center: (f64, f64), radius: f64, start: f64, end: f64) -> Vec<Pt<i32>> {
+    if radius <= 0.0 {
+        return Vec::new();
+    }
+    // step by roughly half a pixel along the circumference so no gaps are left
+    let steps = ((end - start) * radius * 2.0).ceil().max(1.0) as usize;
+    let step = (end - start) / steps as f64;
+    let mut pts: Vec<Pt<i32>> = Vec::with_capacity(steps + 1);
+    for i in 0..=steps {
+        let pt = Pt::from_radian(start + step * i as f64, radius, center).i32();
+        match pts.last() {
+            Some(last) if last.x == pt.x && last.y == pt.y => {}
+            _ => pts.push(pt),
+        }
+    }
+    pts
+}
+
+pub(crate) fn draw_arc(
+    image: &mut RgbaImage,
+    center: (f64, f64),
+    radius: f64,
+    start: f64,
+    end: f64,
+    color: Rgba<u8>,
+) {
+    for pt in arc_points(center, radius, start, end) {
+        super::plot(image, pt, color);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::f64::consts::PI;
     #[test]
     fn torus() -> Result<(), image::ImageError> {
         let mut image = crate::test::blank(400);
@@ -0,0 +1,112 @@
+use super::Pt;
+use image::{Rgba, RgbaImage};
+
+/// A closed polygon.  Side `n` runs from vertex `n` to vertex `n + 1`, wrapping back to the first vertex.
+#[derive(Clone, Debug)]
+pub(crate) struct Polygon {
+    pts: Vec<Pt<f64>>,
+}
+
+impl Polygon {
+    pub(crate) fn new(pts: Vec<Pt<f64>>) -> Self {
+        Self { pts }
+    }
+
+    pub(crate) fn pts(&self) -> &[Pt<f64>] {
+        &self.pts
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pts.len()
+    }
+
+    pub(crate) fn side(&self, n: usize) -> (Pt<f64>, Pt<f64>) {
+        (self.pts[n], self.pts[(n + 1) % self.pts.len()])
+    }
+
+    /// Midpoint of a side
+    pub(crate) fn mid(&self, n: usize) -> Pt<f64> {
+        let (a, b) = self.side(n);
+        Pt::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0)
+    }
+
+    /// Average of all vertices
+    pub(crate) fn center(&self) -> Pt<f64> {
+        let len = self.pts.len().max(1) as f64;
+        let (x, y) = self
+            .pts
+            .iter()
+            .fold((0.0, 0.0), |acc, pt| (acc.0 + pt.x, acc.1 + pt.y));
+        Pt::new(x / len, y / len)
+    }
+
+    /// Top left corner of the bounding box
+    pub(crate) fn min(&self) -> Pt<f64> {
+        self.pts
+            .iter()
+            .fold(Pt::new(f64::INFINITY, f64::INFINITY), |acc, pt| {
+                Pt::new(acc.x.min(pt.x), acc.y.min(pt.y))
+            })
+    }
+
+    /// Bottom right corner of the bounding box
+    pub(crate) fn max(&self) -> Pt<f64> {
+        self.pts
+            .iter()
+            .fold(Pt::new(f64::NEG_INFINITY, f64::NEG_INFINITY), |acc, pt| {
+                Pt::new(acc.x.max(pt.x), acc.y.max(pt.y))
+            })
+    }
+
+    pub(crate) fn translate(&self, x: f64, y: f64) -> Self {
+        Self {
+            pts: self
+                .pts
+                .iter()
+                .map(|pt| Pt::new(pt.x + x, pt.y + y))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn fill(&self, image: &mut RgbaImage, color: Rgba<u8>) {
+        let mut pts: Vec<imageproc::point::Point<i32>> = Vec::with_capacity(self.pts.len());
+        for pt in self.pts.iter().map(|pt| pt.i32()) {
+            let pt = imageproc::point::Point::new(pt.x, pt.y);
+            if pts.last() != Some(&pt) {
+                pts.push(pt);
+            }
+        }
+        while pts.len() > 1 && pts.first() == pts.last() {
+            pts.pop();
+        }
+        if pts.len() > 2 {
+            imageproc::drawing::draw_polygon_mut(image, &pts, color);
+        }
+    }
+
+    pub(crate) fn draw_side(&self, n: usize, image: &mut RgbaImage, color: Rgba<u8>) {
+        let (a, b) = self.side(n);
+        draw_line(image, a, b, color);
+    }
+
+    pub(crate) fn draw_dashed_side(
+        &self,
+        n: usize,
+        width: u32,
+        opacity: f32,
+        image: &mut RgbaImage,
+        color: Rgba<u8>,
+    ) {
+        let (a, b) = self.side(n);
+        super::draw_dashed(&super::line_points(a, b), width, opacity, image, color);
+    }
+}
+
+pub(crate) fn draw_line(image: &mut RgbaImage, a: Pt<f64>, b: Pt<f64>, color: Rgba<u8>) {
+    imageproc::drawing::draw_line_segment_mut(
+        image,
+        (a.x as f32, a.y as f32),
+        (b.x as f32, b.y as f32),
+        color,
+    );
+}
@@ -127,7 +127,8 @@ impl<V> Undirected<V> {
         let mut cells: Vec<SmallVec<[Option<usize>; DEFAULT_NEIGHBORS]>> = (0..grid.len())
             .map(|i| (0..grid.node(i).max_neighbors()).map(|_| None).collect())
             .collect();
-        let mut edges: Vec<UndirEdge<V>> = Vec::with_capacity(grid.len() * G::Node::N); // allocate enough space to accomodate all edges - even in a wrap-around style grid (e.g., a cyclinder)
+        let sides: usize = cells.iter().map(|c| c.len()).sum(); // nodes may have different numbers of neighbors - don't assume `G::Node::N` for all of them
+        let mut edges: Vec<UndirEdge<V>> = Vec::with_capacity(sides); // allocate enough space to accomodate all edges - even in a wrap-around style grid (e.g., a cyclinder)
         let mut outside: Vec<(Conn, V)> = Vec::with_capacity(grid.len()); // default capacity could be improved here with a Graph method
         for cell in grid.nodes() {
             for (i, neighbor) in cell.all_neighbors().iter().enumerate() {
@@ -158,6 +159,11 @@ impl<V> Undirected<V> {
         }
     }
 
+    /// Number of nodes the edges were created for
+    pub(crate) fn len(&self) -> usize {
+        self.cells.len()
+    }
+
     pub(crate) fn iter(&self) -> std::slice::Iter<UndirEdge<V>> {
         self.edges.iter()
     }
@@ -10,6 +10,8 @@ pub enum Error {
     AlreadyLinked(usize, usize),
     #[error("Unlink operation failed: node {0} is not linked with {1}")]
     AlreadyUnlinked(usize, usize),
+    #[error("Duplicate neighbor: node {1} is already a neighbor of node {0}")]
+    DuplicateNeighbor(usize, usize),
     #[error("Invalid cell: id {0} was specified but grid only contains {1} nodes")]
     InvalidCell(usize, usize),
     #[error("Invalid id: graph contains only {1} nodes and node {0} was specified")]
@@ -20,6 +22,20 @@ pub enum Error {
     InvalidEdge(usize, usize),
     #[error("Mismatched list size: list has a length of {0} while graph has a length of {1}")]
     MismatchedListSize(usize, usize),
+    #[error(
+        "Invalid weights: weights must not be empty or negative, and at least one must be positive"
+    )]
+    InvalidWeights(),
+    #[error("Masked graph: this algorithm does not support masked graphs")]
+    MaskedGraph(),
+    #[error("Linked graph: node {0} already has links but this algorithm needs an unlinked graph")]
+    LinkedGraph(usize),
+    #[error(
+        "Unlinked graph: node {0} is missing links but this algorithm needs a fully linked graph"
+    )]
+    UnlinkedGraph(usize),
+    #[error("Wrapped graph: this algorithm does not support graphs that wrap around")]
+    WrappedGraph(),
     #[error("No path available for node {0}")]
     NoPathAvailable(usize),
     #[error("Invalid path add: path already contains node {0}")]
@@ -1,5 +1,14 @@
+pub(crate) mod delta;
+pub(crate) mod hex;
+pub(crate) mod levels;
 pub(crate) mod orth;
-pub(crate) mod weight;
+pub(crate) mod polar;
+pub(crate) mod poly;
+pub(crate) mod sparse;
+pub(crate) mod upsilon;
+pub(crate) mod voronoi;
+pub(crate) mod weave;
+pub mod weight;
 
 use smallvec::SmallVec;
 
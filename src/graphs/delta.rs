@@ -0,0 +1,292 @@
+pub(crate) mod cell;
+
+use super::{Graph, Node};
+use crate::render::state::graph::{Builder, BuilderGraph};
+use crate::render::{RenderBlock, RenderGraph};
+use cell::DeltaCell;
+use image::{Rgba, RgbaImage};
+
+/// A grid of triangular cells that alternate between pointing up and down.
+///
+/// The first cell of the first row points up.
+#[derive(Clone, Debug)]
+pub struct Delta {
+    len: usize,
+    height: usize,
+    width: usize,
+    cells: Vec<DeltaCell>,
+}
+
+impl Delta {
+    /// Create a new triangular grid without any links between cells
+    pub fn new(height: usize, width: usize) -> Self {
+        let len = height * width;
+        Self {
+            len,
+            height,
+            width,
+            cells: (0..len)
+                .map(|id| Self::new_cell(id, height, width))
+                .collect(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Use default rendering options to render an image
+    pub fn render(&self) -> RgbaImage {
+        self.build_render().finish().render()
+    }
+
+    /// Customize the renderer using a reference to the current graph (preferred)
+    pub fn build_render(&self) -> BuilderGraph<'_, Self> {
+        Builder::graph(self)
+    }
+
+    /// Customize the renderer using the current owned graph
+    pub fn build_render_owned<'g>(self) -> BuilderGraph<'g, Self> {
+        Builder::owned_graph(self)
+    }
+
+    /// Create a distance map from the current graph and a given starting point
+    pub fn dist(&self, start: usize) -> crate::Dist {
+        crate::Dist::simple(self, start)
+    }
+
+    /// Find the shortest path using a given distance map
+    pub fn shortest_path(
+        &self,
+        dist: &crate::Dist,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        dist.shortest_path(self, end)
+    }
+
+    /// Find the shortest path using a weight of 1 for all edges
+    pub fn shortest_path_simple(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        self.dist(start).shortest_path(self, end)
+    }
+
+    fn check_id(&self, cell: usize) -> Result<(), crate::Error> {
+        match cell >= self.len {
+            true => Err(crate::Error::InvalidCell(cell, self.len)),
+            false => Ok(()),
+        }
+    }
+
+    fn new_cell(id: usize, height: usize, width: usize) -> DeltaCell {
+        let (row, col) = (id / width, id % width);
+        let left = if col == 0 { None } else { Some(id - 1) };
+        let right = if col + 1 == width { None } else { Some(id + 1) };
+        let n = if (row + col) % 2 == 0 {
+            let below = if row + 1 == height {
+                None
+            } else {
+                Some(id + width)
+            };
+            [right, below, left]
+        } else {
+            let above = if row == 0 { None } else { Some(id - width) };
+            [above, right, left]
+        };
+        DeltaCell {
+            id,
+            row: row as u32,
+            col: col as u32,
+            n,
+            links: [None; 3],
+        }
+    }
+}
+
+impl Graph for Delta {
+    type Node = DeltaCell;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn node(&self, id: usize) -> &Self::Node {
+        &self.cells[id]
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Self::Node {
+        &mut self.cells[id]
+    }
+
+    fn nodes(&self) -> Box<dyn Iterator<Item = &Self::Node> + '_> {
+        Box::new(self.cells.iter())
+    }
+
+    fn nodes_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Node> + '_> {
+        Box::new(self.cells.iter_mut())
+    }
+
+    fn link(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| self.cells[a].link(b).and_then(|_| self.cells[b].link(a)))
+    }
+
+    fn unlink(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| {
+                self.cells[a]
+                    .unlink(b)
+                    .and_then(|_| self.cells[b].unlink(a))
+            })
+    }
+}
+
+impl RenderGraph for Delta {
+    fn size(&self, block_height: u32, block_width: u32, padding: u32) -> (u32, u32) {
+        // the +1 accounts for borders, assuming border size of 1
+        (
+            (self.width as u32 + 1) * block_width / 2 + 1 + padding + padding,
+            self.height as u32 * block_height + 1 + padding + padding,
+        )
+    }
+
+    fn blocks(&self, height: u32, width: u32, padding: u32) -> Vec<<Self::Node as Node>::Block> {
+        self.cells
+            .iter()
+            .map(|c| c.block(height, width, padding))
+            .collect()
+    }
+
+    fn fill(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        color: &Rgba<u8>,
+        img: &mut RgbaImage,
+    ) {
+        cell.fill(block, color, img);
+    }
+
+    fn blend_fill(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        i: usize,
+        max: usize,
+        blend: &crate::render::opts::blend::Blend,
+        image: &mut RgbaImage,
+    ) {
+        cell.blend_fill(block, i, max, blend, image);
+    }
+
+    fn text_pos(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        center: bool,
+        padding: freehand::Pt<i32>,
+    ) -> freehand::Pt<u32> {
+        cell.text_pos(block, center, padding)
+    }
+
+    fn edge(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        n: usize,
+        dash_width: u32,
+        unlinked_color: &Rgba<u8>,
+        linked_color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if !cell.linked_side(n) {
+            cell.edge_unlinked(block, n, unlinked_color, image);
+        } else if dash_width != 0 {
+            cell.edge_linked(block, n, dash_width, linked_color, image);
+        }
+    }
+
+    fn arrow(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        cell.arrow(block, from_n, to_n, style, color, image);
+    }
+
+    fn half_arrow(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        cell.half_arrow(block, n, style, color, image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn delta_neighbors() {
+        crate::logger(log::LevelFilter::Warn);
+        let grid = Delta::new(2, 3);
+        assert_eq!(grid.cells[0].n, [Some(1), Some(3), None]);
+        assert_eq!(grid.cells[1].n, [None, Some(2), Some(0)]);
+        assert_eq!(grid.cells[2].n, [None, Some(5), Some(1)]);
+        assert_eq!(grid.cells[3].n, [Some(0), Some(4), None]);
+        assert_eq!(grid.cells[4].n, [Some(5), None, Some(3)]);
+        assert_eq!(grid.cells[5].n, [Some(2), None, Some(4)]);
+    }
+
+    #[test]
+    fn delta_path() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Delta::new(6, 11);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let renderer = grid.build_render().finish();
+        let path_renderer = crate::render::state::path::Builder::render_state(&renderer)
+            .default_opts()
+            .simplified_path(0, grid.len() - 1)
+            .finish();
+        path_renderer.render().save("images/tests/delta_path.png")
+    }
+
+    #[test]
+    fn delta_bezier_path() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Delta::new(5, 7);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let path = grid.shortest_path_simple(0, grid.len() - 1).unwrap();
+        let opts = crate::render::opts::PathOpts::build()
+            .arrow_style(crate::render::opts::Arrow::BezierQuad(0.25, 0.75))
+            .build();
+        let renderer = grid.build_render().finish();
+        crate::render::state::path::Builder::render_state(&renderer)
+            .owned_opts(opts)
+            .owned_path(path)
+            .finish()
+            .render();
+    }
+}
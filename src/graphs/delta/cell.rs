@@ -0,0 +1,242 @@
+use crate::drawing::poly::{draw_line, Polygon};
+use crate::drawing::Pt;
+use crate::graphs::{Block, Neighbors, Node};
+use crate::render::opts::blend::Blend;
+use crate::Error;
+use freehand::Pt as FPt;
+use image::{Rgba, RgbaImage};
+
+#[derive(Clone, Debug)]
+pub struct DeltaBlock {
+    poly: Polygon,
+}
+
+impl DeltaBlock {
+    fn center(&self) -> Pt<f64> {
+        self.poly.center()
+    }
+
+    /// Midpoint of the specified edge
+    fn mid(&self, n: usize) -> Pt<f64> {
+        match n {
+            0..=2 => self.poly.mid(n),
+            _ => panic!("Invalid edge {}", n),
+        }
+    }
+}
+impl Block for DeltaBlock {}
+
+/// A triangular cell in a [`Delta`](super::Delta) graph.
+///
+/// Cells pointing up have their sides ordered: right, bottom, left.
+/// Cells pointing down have their sides ordered: top, right, left.
+#[derive(Clone, Debug)]
+pub struct DeltaCell {
+    pub(super) id: usize,
+    pub(super) row: u32,
+    pub(super) col: u32,
+    pub(super) n: [Option<usize>; 3], // neighbors
+    pub(super) links: [Option<usize>; 3],
+}
+
+impl DeltaCell {
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    /// Whether the triangle points up (has a horizontal bottom edge)
+    pub fn upward(&self) -> bool {
+        (self.row + self.col) % 2 == 0
+    }
+}
+
+impl Node for DeltaCell {
+    type Block = DeltaBlock;
+    const N: usize = 3;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn all_neighbors(&self) -> &[Option<usize>] {
+        &self.n
+    }
+
+    fn neighbors(&self) -> crate::graphs::Neighbors {
+        crate::graphs::Neighbors::new(&self.n[..])
+    }
+
+    fn num_neighbors(&self) -> usize {
+        self.n
+            .iter()
+            .fold(0usize, |acc, n| acc + n.is_some() as usize)
+    }
+
+    fn neighbor(&self, n: usize) -> Option<usize> {
+        self.n[n]
+    }
+
+    fn neighbor_id(&self, cell: usize) -> Option<usize> {
+        self.n
+            .iter()
+            .position(|n| matches!(n, Some(n) if *n == cell))
+    }
+
+    fn links(&self) -> Neighbors {
+        Neighbors::new(&self.links)
+    }
+
+    fn linked_to(&self, id: usize) -> bool {
+        match self.neighbor_id(id) {
+            Some(n) => self.links[n].is_some(),
+            None => false,
+        }
+    }
+
+    fn linked_side(&self, n: usize) -> bool {
+        self.links[n].is_some()
+    }
+
+    fn link(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_none() {
+                    self.links[n] = Some(cell);
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyLinked(self.id, cell))
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_some() {
+                    self.links[n] = None;
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyUnlinked(self.id, cell))
+                }
+            }
+        }
+    }
+}
+
+impl crate::render::RenderBlock for DeltaCell {
+    /// `width` is the length of a triangle's horizontal edge and `height` its height
+    fn block(&self, height: u32, width: u32, padding: u32) -> Self::Block {
+        let (h, w) = (height as f64, width as f64);
+        let x = self.col as f64 * w / 2.0 + padding as f64;
+        let y = self.row as f64 * h + padding as f64;
+        let pts = if self.upward() {
+            vec![
+                Pt::new(x + w / 2.0, y),
+                Pt::new(x + w, y + h),
+                Pt::new(x, y + h),
+            ]
+        } else {
+            vec![
+                Pt::new(x, y),
+                Pt::new(x + w, y),
+                Pt::new(x + w / 2.0, y + h),
+            ]
+        };
+        DeltaBlock {
+            poly: Polygon::new(pts),
+        }
+    }
+
+    fn fill(&self, block: &Self::Block, color: &Rgba<u8>, image: &mut RgbaImage) {
+        block.poly.fill(image, *color);
+    }
+
+    fn blend_fill(
+        &self,
+        block: &Self::Block,
+        i: usize,
+        max: usize,
+        blend: &Blend,
+        image: &mut RgbaImage,
+    ) {
+        self.fill(block, &blend.color(i, max), image);
+    }
+
+    fn edge_unlinked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        block.poly.draw_side(n, image, *color);
+    }
+
+    fn edge_linked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        width: u32,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        block
+            .poly
+            .draw_dashed_side(n, width, color[3] as f32 / 255.0, image, *color);
+    }
+
+    fn text_pos(&self, block: &Self::Block, center: bool, padding: FPt<i32>) -> FPt<u32> {
+        let pt = if center {
+            block.center().i32()
+        } else {
+            block.poly.min().i32()
+        };
+        FPt::new(pt.x + padding.x(), pt.y + padding.y()).u32()
+    }
+
+    fn arrow(
+        &self,
+        block: &Self::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            Arrow::Straight => draw_line(image, block.mid(from_n), block.mid(to_n), color),
+            // bezier curves fall back to straight lines through the center
+            Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                self.half_arrow(block, from_n, style, color, image);
+                self.half_arrow(block, to_n, style, color, image);
+            }
+        }
+    }
+
+    fn half_arrow(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            // bezier curves fall back to straight lines
+            Arrow::Straight | Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                draw_line(image, block.mid(n), block.center(), color)
+            }
+        }
+    }
+}
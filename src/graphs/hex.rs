@@ -0,0 +1,369 @@
+pub(crate) mod cell;
+
+use super::{Graph, Node};
+use crate::render::state::graph::{Builder, BuilderGraph};
+use crate::render::{RenderBlock, RenderGraph};
+use cell::{HexCell, HexOrientation};
+use image::{Rgba, RgbaImage};
+
+/// A grid of hexagonal cells, each with up to six neighbors
+#[derive(Clone, Debug)]
+pub struct Hex {
+    len: usize,
+    height: usize,
+    width: usize,
+    orientation: HexOrientation,
+    cells: Vec<HexCell>,
+}
+
+impl Hex {
+    /// Create a new hexagonal grid without any links between cells
+    pub fn new(height: usize, width: usize, orientation: HexOrientation) -> Self {
+        let len = height * width;
+        Self {
+            len,
+            height,
+            width,
+            orientation,
+            cells: (0..len)
+                .map(|id| Self::new_cell(id, height, width, orientation))
+                .collect(),
+        }
+    }
+
+    /// Create a new grid of hexagons with a corner at the top
+    pub fn pointy(height: usize, width: usize) -> Self {
+        Self::new(height, width, HexOrientation::PointyTop)
+    }
+
+    /// Create a new grid of hexagons with a flat side at the top
+    pub fn flat(height: usize, width: usize) -> Self {
+        Self::new(height, width, HexOrientation::FlatTop)
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn orientation(&self) -> HexOrientation {
+        self.orientation
+    }
+
+    /// Use default rendering options to render an image
+    pub fn render(&self) -> RgbaImage {
+        self.build_render().finish().render()
+    }
+
+    /// Customize the renderer using a reference to the current graph (preferred)
+    pub fn build_render(&self) -> BuilderGraph<'_, Self> {
+        Builder::graph(self)
+    }
+
+    /// Customize the renderer using the current owned graph
+    pub fn build_render_owned<'g>(self) -> BuilderGraph<'g, Self> {
+        Builder::owned_graph(self)
+    }
+
+    /// Create a distance map from the current graph and a given starting point
+    pub fn dist(&self, start: usize) -> crate::Dist {
+        crate::Dist::simple(self, start)
+    }
+
+    /// Find the shortest path using a given distance map
+    pub fn shortest_path(
+        &self,
+        dist: &crate::Dist,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        dist.shortest_path(self, end)
+    }
+
+    /// Find the shortest path using a weight of 1 for all edges
+    pub fn shortest_path_simple(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        self.dist(start).shortest_path(self, end)
+    }
+
+    fn check_id(&self, cell: usize) -> Result<(), crate::Error> {
+        match cell >= self.len {
+            true => Err(crate::Error::InvalidCell(cell, self.len)),
+            false => Ok(()),
+        }
+    }
+
+    fn cell_id(row: isize, col: isize, height: usize, width: usize) -> Option<usize> {
+        if row < 0 || col < 0 || row >= height as isize || col >= width as isize {
+            None
+        } else {
+            Some(row as usize * width + col as usize)
+        }
+    }
+
+    fn new_cell(id: usize, height: usize, width: usize, orientation: HexOrientation) -> HexCell {
+        let (row, col) = ((id / width) as isize, (id % width) as isize);
+        let at = |r: isize, c: isize| Self::cell_id(r, c, height, width);
+        let n = match orientation {
+            HexOrientation::PointyTop => {
+                // odd rows are shifted right
+                let shift = row % 2;
+                [
+                    at(row - 1, col + shift),
+                    at(row, col + 1),
+                    at(row + 1, col + shift),
+                    at(row + 1, col + shift - 1),
+                    at(row, col - 1),
+                    at(row - 1, col + shift - 1),
+                ]
+            }
+            HexOrientation::FlatTop => {
+                // odd columns are shifted down
+                let shift = col % 2;
+                [
+                    at(row - 1, col),
+                    at(row + shift - 1, col + 1),
+                    at(row + shift, col + 1),
+                    at(row + 1, col),
+                    at(row + shift, col - 1),
+                    at(row + shift - 1, col - 1),
+                ]
+            }
+        };
+        HexCell {
+            id,
+            row: row as u32,
+            col: col as u32,
+            orientation,
+            n,
+            links: [None; 6],
+        }
+    }
+}
+
+impl Graph for Hex {
+    type Node = HexCell;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn node(&self, id: usize) -> &Self::Node {
+        &self.cells[id]
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Self::Node {
+        &mut self.cells[id]
+    }
+
+    fn nodes(&self) -> Box<dyn Iterator<Item = &Self::Node> + '_> {
+        Box::new(self.cells.iter())
+    }
+
+    fn nodes_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Node> + '_> {
+        Box::new(self.cells.iter_mut())
+    }
+
+    fn link(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| self.cells[a].link(b).and_then(|_| self.cells[b].link(a)))
+    }
+
+    fn unlink(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| {
+                self.cells[a]
+                    .unlink(b)
+                    .and_then(|_| self.cells[b].unlink(a))
+            })
+    }
+}
+
+impl RenderGraph for Hex {
+    fn size(&self, block_height: u32, block_width: u32, padding: u32) -> (u32, u32) {
+        let (h, w) = (block_height as f64, block_width as f64);
+        let (rows, cols) = (self.height as f64, self.width as f64);
+        let (x, y) = match self.orientation {
+            HexOrientation::PointyTop => (
+                w * cols + (self.height > 1) as u8 as f64 * w / 2.0,
+                h + (rows - 1.0).max(0.0) * h * 0.75,
+            ),
+            HexOrientation::FlatTop => (
+                w + (cols - 1.0).max(0.0) * w * 0.75,
+                h * rows + (self.width > 1) as u8 as f64 * h / 2.0,
+            ),
+        };
+        // the +1 accounts for borders, assuming border size of 1
+        (
+            x.ceil() as u32 + 1 + padding + padding,
+            y.ceil() as u32 + 1 + padding + padding,
+        )
+    }
+
+    fn blocks(&self, height: u32, width: u32, padding: u32) -> Vec<<Self::Node as Node>::Block> {
+        self.cells
+            .iter()
+            .map(|c| c.block(height, width, padding))
+            .collect()
+    }
+
+    fn fill(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        color: &Rgba<u8>,
+        img: &mut RgbaImage,
+    ) {
+        cell.fill(block, color, img);
+    }
+
+    fn blend_fill(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        i: usize,
+        max: usize,
+        blend: &crate::render::opts::blend::Blend,
+        image: &mut RgbaImage,
+    ) {
+        cell.blend_fill(block, i, max, blend, image);
+    }
+
+    fn text_pos(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        center: bool,
+        padding: freehand::Pt<i32>,
+    ) -> freehand::Pt<u32> {
+        cell.text_pos(block, center, padding)
+    }
+
+    fn edge(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        n: usize,
+        dash_width: u32,
+        unlinked_color: &Rgba<u8>,
+        linked_color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if !cell.linked_side(n) {
+            cell.edge_unlinked(block, n, unlinked_color, image);
+        } else if dash_width != 0 {
+            cell.edge_linked(block, n, dash_width, linked_color, image);
+        }
+    }
+
+    fn arrow(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        cell.arrow(block, from_n, to_n, style, color, image);
+    }
+
+    fn half_arrow(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        cell.half_arrow(block, n, style, color, image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn hex_neighbors() {
+        crate::logger(log::LevelFilter::Warn);
+        let grid = Hex::pointy(3, 3);
+        assert_eq!(grid.cells[0].n, [None, Some(1), Some(3), None, None, None]);
+        assert_eq!(
+            grid.cells[4].n,
+            [Some(2), Some(5), Some(8), Some(7), Some(3), Some(1)]
+        );
+        assert_eq!(
+            grid.cells[3].n,
+            [Some(1), Some(4), Some(7), Some(6), None, Some(0)]
+        );
+
+        let grid = Hex::flat(3, 3);
+        assert_eq!(grid.cells[0].n, [None, None, Some(1), Some(3), None, None]);
+        assert_eq!(
+            grid.cells[1].n,
+            [None, Some(2), Some(5), Some(4), Some(3), Some(0)]
+        );
+        assert_eq!(
+            grid.cells[4].n,
+            [Some(1), Some(5), Some(8), Some(7), Some(6), Some(3)]
+        );
+
+        for grid in [Hex::pointy(5, 4), Hex::flat(4, 5)] {
+            for cell in grid.nodes() {
+                for n in cell.neighbors() {
+                    assert!(grid.node(*n).neighbor_id(cell.id()).is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hex_path() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        for (name, mut grid) in [("pointy", Hex::pointy(7, 7)), ("flat", Hex::flat(7, 7))] {
+            let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+            crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+            let dist = grid.dist(0);
+            let path = grid.shortest_path(&dist, grid.len() - 1).unwrap();
+            let renderer = grid.build_render().finish();
+            let path_renderer = crate::render::state::path::Builder::render_state(&renderer)
+                .default_opts()
+                .owned_path(path)
+                .finish();
+            path_renderer
+                .render()
+                .save(format!("images/tests/hex_{}_path.png", name))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hex_bezier_path() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Hex::pointy(5, 5);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let path = grid.shortest_path_simple(0, grid.len() - 1).unwrap();
+        let opts = crate::render::opts::PathOpts::build()
+            .arrow_style(crate::render::opts::Arrow::BezierQuad(0.25, 0.75))
+            .build();
+        let renderer = grid.build_render().finish();
+        crate::render::state::path::Builder::render_state(&renderer)
+            .owned_opts(opts)
+            .owned_path(path)
+            .finish()
+            .render();
+    }
+}
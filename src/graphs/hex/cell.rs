@@ -0,0 +1,270 @@
+use crate::drawing::poly::{draw_line, Polygon};
+use crate::drawing::Pt;
+use crate::graphs::{Block, Neighbors, Node};
+use crate::render::opts::blend::Blend;
+use crate::Error;
+use freehand::Pt as FPt;
+use image::{Rgba, RgbaImage};
+
+/// Which way the hexagons in a [`Hex`](super::Hex) graph point
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HexOrientation {
+    /// Hexagons have a corner at the top.  Odd rows are shifted right by half a cell.
+    ///
+    /// Sides are ordered: NE, E, SE, SW, W, NW
+    #[default]
+    PointyTop,
+    /// Hexagons have a flat side at the top.  Odd columns are shifted down by half a cell.
+    ///
+    /// Sides are ordered: N, NE, SE, S, SW, NW
+    FlatTop,
+}
+
+#[derive(Clone, Debug)]
+pub struct HexBlock {
+    poly: Polygon,
+}
+
+impl HexBlock {
+    fn center(&self) -> Pt<f64> {
+        self.poly.center()
+    }
+
+    fn mid(&self, n: usize) -> Pt<f64> {
+        match n {
+            0..=5 => self.poly.mid(n),
+            _ => panic!("Invalid edge {}", n),
+        }
+    }
+}
+impl Block for HexBlock {}
+
+#[derive(Clone, Debug)]
+pub struct HexCell {
+    pub(super) id: usize,
+    pub(super) row: u32,
+    pub(super) col: u32,
+    pub(super) orientation: HexOrientation,
+    pub(super) n: [Option<usize>; 6], // neighbors
+    pub(super) links: [Option<usize>; 6],
+}
+
+impl HexCell {
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    /// Corners relative to the center of the cell, ordered so that side `n` is between corner `n` and `n + 1`
+    fn corners(orientation: HexOrientation, height: f64, width: f64) -> [Pt<f64>; 6] {
+        let (h, w) = (height / 2.0, width / 2.0);
+        match orientation {
+            HexOrientation::PointyTop => [
+                Pt::new(0.0, -h),
+                Pt::new(w, -h / 2.0),
+                Pt::new(w, h / 2.0),
+                Pt::new(0.0, h),
+                Pt::new(-w, h / 2.0),
+                Pt::new(-w, -h / 2.0),
+            ],
+            HexOrientation::FlatTop => [
+                Pt::new(-w / 2.0, -h),
+                Pt::new(w / 2.0, -h),
+                Pt::new(w, 0.0),
+                Pt::new(w / 2.0, h),
+                Pt::new(-w / 2.0, h),
+                Pt::new(-w, 0.0),
+            ],
+        }
+    }
+}
+
+impl Node for HexCell {
+    type Block = HexBlock;
+    const N: usize = 6;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn all_neighbors(&self) -> &[Option<usize>] {
+        &self.n
+    }
+
+    fn neighbors(&self) -> crate::graphs::Neighbors {
+        crate::graphs::Neighbors::new(&self.n[..])
+    }
+
+    fn num_neighbors(&self) -> usize {
+        self.n
+            .iter()
+            .fold(0usize, |acc, n| acc + n.is_some() as usize)
+    }
+
+    fn neighbor(&self, n: usize) -> Option<usize> {
+        self.n[n]
+    }
+
+    fn neighbor_id(&self, cell: usize) -> Option<usize> {
+        self.n
+            .iter()
+            .position(|n| matches!(n, Some(n) if *n == cell))
+    }
+
+    fn links(&self) -> Neighbors {
+        Neighbors::new(&self.links)
+    }
+
+    fn linked_to(&self, id: usize) -> bool {
+        match self.neighbor_id(id) {
+            Some(n) => self.links[n].is_some(),
+            None => false,
+        }
+    }
+
+    fn linked_side(&self, n: usize) -> bool {
+        self.links[n].is_some()
+    }
+
+    fn link(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_none() {
+                    self.links[n] = Some(cell);
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyLinked(self.id, cell))
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_some() {
+                    self.links[n] = None;
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyUnlinked(self.id, cell))
+                }
+            }
+        }
+    }
+}
+
+impl crate::render::RenderBlock for HexCell {
+    /// `width` and `height` are the full width and height of a single hexagon
+    fn block(&self, height: u32, width: u32, padding: u32) -> Self::Block {
+        let (h, w) = (height as f64, width as f64);
+        let (row, col) = (self.row as f64, self.col as f64);
+        let center = match self.orientation {
+            HexOrientation::PointyTop => Pt::new(
+                w / 2.0 + col * w + (self.row % 2) as f64 * w / 2.0,
+                h / 2.0 + row * h * 0.75,
+            ),
+            HexOrientation::FlatTop => Pt::new(
+                w / 2.0 + col * w * 0.75,
+                h / 2.0 + row * h + (self.col % 2) as f64 * h / 2.0,
+            ),
+        };
+        let pts = Self::corners(self.orientation, h, w)
+            .iter()
+            .map(|pt| Pt::new(pt.x + center.x, pt.y + center.y))
+            .collect();
+        HexBlock {
+            poly: Polygon::new(pts).translate(padding as f64, padding as f64),
+        }
+    }
+
+    fn fill(&self, block: &Self::Block, color: &Rgba<u8>, image: &mut RgbaImage) {
+        block.poly.fill(image, *color);
+    }
+
+    fn blend_fill(
+        &self,
+        block: &Self::Block,
+        i: usize,
+        max: usize,
+        blend: &Blend,
+        image: &mut RgbaImage,
+    ) {
+        self.fill(block, &blend.color(i, max), image);
+    }
+
+    fn edge_unlinked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        block.poly.draw_side(n, image, *color);
+    }
+
+    fn edge_linked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        width: u32,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        block
+            .poly
+            .draw_dashed_side(n, width, color[3] as f32 / 255.0, image, *color);
+    }
+
+    fn text_pos(&self, block: &Self::Block, center: bool, padding: FPt<i32>) -> FPt<u32> {
+        let pt = if center {
+            block.center().i32()
+        } else {
+            block.poly.min().i32()
+        };
+        FPt::new(pt.x + padding.x(), pt.y + padding.y()).u32()
+    }
+
+    fn arrow(
+        &self,
+        block: &Self::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            Arrow::Straight => draw_line(image, block.mid(from_n), block.mid(to_n), color),
+            // bezier curves fall back to straight lines through the center
+            Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                self.half_arrow(block, from_n, style, color, image);
+                self.half_arrow(block, to_n, style, color, image);
+            }
+        }
+    }
+
+    fn half_arrow(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            // bezier curves fall back to straight lines
+            Arrow::Straight | Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                draw_line(image, block.mid(n), block.center(), color)
+            }
+        }
+    }
+}
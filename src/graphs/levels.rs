@@ -0,0 +1,339 @@
+pub(crate) mod cell;
+
+use super::{Graph, Node};
+use crate::render::state::graph::{Builder, BuilderGraph};
+use crate::render::{RenderBlock, RenderGraph};
+use cell::LevelCell;
+use image::{Rgba, RgbaImage};
+
+/// A stack of rectangular grids, where cells may also link to the cells directly above and below them.
+///
+/// Levels are rendered side by side, from the bottom level on the left to the top level on the right.
+#[derive(Clone, Debug)]
+pub struct Levels {
+    len: usize,
+    levels: usize,
+    height: usize,
+    width: usize,
+    cells: Vec<LevelCell>,
+}
+
+impl Levels {
+    /// Create a new multi-level grid without any links between cells
+    pub fn new(levels: usize, height: usize, width: usize) -> Self {
+        let len = levels * height * width;
+        Self {
+            len,
+            levels,
+            height,
+            width,
+            cells: (0..len)
+                .map(|id| Self::new_cell(id, levels, height, width))
+                .collect(),
+        }
+    }
+
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Id of the cell at the given position, if it exists
+    pub fn cell_id(&self, level: usize, row: usize, col: usize) -> Option<usize> {
+        match level < self.levels && row < self.height && col < self.width {
+            true => Some((level * self.height + row) * self.width + col),
+            false => None,
+        }
+    }
+
+    /// Use default rendering options to render an image
+    pub fn render(&self) -> RgbaImage {
+        self.build_render().finish().render()
+    }
+
+    /// Customize the renderer using a reference to the current graph (preferred)
+    pub fn build_render(&self) -> BuilderGraph<'_, Self> {
+        Builder::graph(self)
+    }
+
+    /// Customize the renderer using the current owned graph
+    pub fn build_render_owned<'g>(self) -> BuilderGraph<'g, Self> {
+        Builder::owned_graph(self)
+    }
+
+    /// Create a distance map from the current graph and a given starting point
+    pub fn dist(&self, start: usize) -> crate::Dist {
+        crate::Dist::simple(self, start)
+    }
+
+    /// Find the shortest path using a given distance map
+    pub fn shortest_path(
+        &self,
+        dist: &crate::Dist,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        dist.shortest_path(self, end)
+    }
+
+    /// Find the shortest path using a weight of 1 for all edges
+    pub fn shortest_path_simple(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        self.dist(start).shortest_path(self, end)
+    }
+
+    fn check_id(&self, cell: usize) -> Result<(), crate::Error> {
+        match cell >= self.len {
+            true => Err(crate::Error::InvalidCell(cell, self.len)),
+            false => Ok(()),
+        }
+    }
+
+    fn new_cell(id: usize, levels: usize, height: usize, width: usize) -> LevelCell {
+        let area = height * width;
+        let (level, row, col) = (id / area, id % area / width, id % width);
+        LevelCell {
+            id,
+            level: level as u32,
+            row: row as u32,
+            col: col as u32,
+            // each level is followed by an empty column to separate it from the next level
+            x: (level * (width + 1) + col) as u32,
+            n: [
+                (row > 0).then(|| id - width),
+                (col + 1 < width).then(|| id + 1),
+                (row + 1 < height).then(|| id + width),
+                (col > 0).then(|| id - 1),
+                (level + 1 < levels).then(|| id + area),
+                (level > 0).then(|| id - area),
+            ],
+            links: [None; 6],
+        }
+    }
+}
+
+impl Graph for Levels {
+    type Node = LevelCell;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn node(&self, id: usize) -> &Self::Node {
+        &self.cells[id]
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Self::Node {
+        &mut self.cells[id]
+    }
+
+    fn nodes(&self) -> Box<dyn Iterator<Item = &Self::Node> + '_> {
+        Box::new(self.cells.iter())
+    }
+
+    fn nodes_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Node> + '_> {
+        Box::new(self.cells.iter_mut())
+    }
+
+    fn link(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| self.cells[a].link(b).and_then(|_| self.cells[b].link(a)))
+    }
+
+    fn unlink(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| {
+                self.cells[a]
+                    .unlink(b)
+                    .and_then(|_| self.cells[b].unlink(a))
+            })
+    }
+}
+
+impl RenderGraph for Levels {
+    fn size(&self, block_height: u32, block_width: u32, padding: u32) -> (u32, u32) {
+        let cols = (self.levels * (self.width + 1)).saturating_sub(1) as u32;
+        (
+            cols * (block_width + 1) + padding + padding,
+            (self.height) as u32 * (block_height + 1) + padding + padding,
+        )
+    }
+
+    fn blocks(&self, height: u32, width: u32, padding: u32) -> Vec<<Self::Node as Node>::Block> {
+        self.cells
+            .iter()
+            .map(|c| c.block(height, width, padding))
+            .collect()
+    }
+
+    fn fill(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        color: &Rgba<u8>,
+        img: &mut RgbaImage,
+    ) {
+        cell.fill(block, color, img);
+    }
+
+    fn blend_fill(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        i: usize,
+        max: usize,
+        blend: &crate::render::opts::blend::Blend,
+        image: &mut RgbaImage,
+    ) {
+        cell.blend_fill(block, i, max, blend, image);
+    }
+
+    fn text_pos(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        center: bool,
+        padding: freehand::Pt<i32>,
+    ) -> freehand::Pt<u32> {
+        cell.text_pos(block, center, padding)
+    }
+
+    /// Stairs are always drawn for links between levels, using the edge color
+    fn edge(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        n: usize,
+        dash_width: u32,
+        unlinked_color: &Rgba<u8>,
+        linked_color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if n >= 4 {
+            if cell.linked_side(n) {
+                cell.edge_linked(block, n, dash_width, unlinked_color, image);
+            }
+        } else if !cell.linked_side(n) {
+            cell.edge_unlinked(block, n, unlinked_color, image);
+        } else if dash_width != 0 {
+            cell.edge_linked(block, n, dash_width, linked_color, image);
+        }
+    }
+
+    fn arrow(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        cell.arrow(block, from_n, to_n, style, color, image);
+    }
+
+    fn half_arrow(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        cell.half_arrow(block, n, style, color, image);
+    }
+
+    /// Links between levels are drawn in both cells, since each level is drawn separately
+    fn split(&self, cell: &Self::Node, n: usize) -> bool {
+        n >= 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn levels_neighbors() {
+        crate::logger(log::LevelFilter::Warn);
+        let grid = Levels::new(3, 3, 3);
+        assert_eq!(
+            grid.cells[0].n,
+            [None, Some(1), Some(3), None, Some(9), None]
+        );
+        assert_eq!(
+            grid.cells[13].n,
+            [Some(10), Some(14), Some(16), Some(12), Some(22), Some(4)]
+        );
+        assert_eq!(
+            grid.cells[26].n,
+            [Some(23), None, None, Some(25), None, Some(17)]
+        );
+        assert_eq!(grid.cell_id(2, 2, 2), Some(26));
+        assert_eq!(grid.cell_id(3, 0, 0), None);
+
+        for cell in grid.nodes() {
+            for n in cell.neighbors() {
+                assert!(grid.node(*n).neighbor_id(cell.id()).is_some());
+            }
+        }
+
+        // stairs use the edge color rather than the seam color
+        let state = grid.build_render().finish();
+        let colors = state.opts().colors();
+        assert_eq!(
+            state.edges().edge_value(0, LevelCell::UP).unwrap(),
+            colors.edges()
+        );
+        assert_eq!(state.edges().edge_value(0, 1).unwrap(), colors.edges());
+    }
+
+    #[test]
+    fn levels_path() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Levels::new(3, 5, 5);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let dist = grid.dist(0);
+        let path = grid.shortest_path(&dist, grid.len() - 1).unwrap();
+        let renderer = grid.build_render().finish();
+        let path_renderer = crate::render::state::path::Builder::render_state(&renderer)
+            .default_opts()
+            .owned_path(path)
+            .finish();
+        path_renderer.render().save("images/tests/levels_path.png")
+    }
+
+    #[test]
+    fn levels_bezier_path() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Levels::new(2, 4, 4);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let path = grid.shortest_path_simple(0, grid.len() - 1).unwrap();
+        let opts = crate::render::opts::PathOpts::build()
+            .arrow_style(crate::render::opts::Arrow::BezierQuad(0.25, 0.75))
+            .build();
+        let renderer = grid.build_render().finish();
+        crate::render::state::path::Builder::render_state(&renderer)
+            .owned_opts(opts)
+            .owned_path(path)
+            .finish()
+            .render();
+    }
+}
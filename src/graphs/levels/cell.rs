@@ -0,0 +1,304 @@
+use crate::drawing::poly::{draw_line, Polygon};
+use crate::drawing::Pt;
+use crate::graphs::{Block, Neighbors, Node};
+use crate::render::opts::blend::Blend;
+use crate::Error;
+use freehand::Pt as FPt;
+use image::{Rgba, RgbaImage};
+
+#[derive(Clone, Debug)]
+pub struct LevelBlock {
+    poly: Polygon,
+}
+
+impl LevelBlock {
+    fn center(&self) -> Pt<f64> {
+        self.poly.center()
+    }
+
+    /// Size of a quarter of the block, used to place the stair glyphs
+    fn quarter(&self) -> Pt<f64> {
+        let (min, max) = (self.poly.min(), self.poly.max());
+        Pt::new((max.x - min.x) / 4.0, (max.y - min.y) / 4.0)
+    }
+
+    /// Midpoint of a wall, or the center of the stair glyph for up and down.
+    ///
+    /// Stairs going up are in the top right quarter and stairs going down are in the bottom left quarter.
+    fn mid(&self, n: usize) -> Pt<f64> {
+        let (c, q) = (self.center(), self.quarter());
+        match n {
+            0..=3 => self.poly.mid(n),
+            LevelCell::UP => Pt::new(c.x + q.x, c.y - q.y),
+            LevelCell::DOWN => Pt::new(c.x - q.x, c.y + q.y),
+            _ => panic!("Invalid edge {}", n),
+        }
+    }
+
+    /// Draw a ladder for a link to another level
+    fn draw_stairs(&self, n: usize, color: Rgba<u8>, image: &mut RgbaImage) {
+        let (c, q) = (self.mid(n), self.quarter());
+        let (w, h) = (q.x / 2.0, q.y * 0.75);
+        let (left, right) = (c.x - w / 2.0, c.x + w / 2.0);
+        draw_line(image, Pt::new(left, c.y - h), Pt::new(left, c.y + h), color);
+        draw_line(
+            image,
+            Pt::new(right, c.y - h),
+            Pt::new(right, c.y + h),
+            color,
+        );
+        for i in 0..3 {
+            let y = c.y - h + (i as f64 + 0.5) * (2.0 * h / 3.0);
+            draw_line(image, Pt::new(left, y), Pt::new(right, y), color);
+        }
+    }
+
+    /// Draw a chevron pointing up or down to show a path changing levels
+    fn draw_level_change(&self, n: usize, color: Rgba<u8>, image: &mut RgbaImage) {
+        let (c, q) = (self.mid(n), self.quarter());
+        let (w, h) = (q.x / 2.0, q.y / 3.0);
+        let h = if n == LevelCell::UP { -h } else { h };
+        draw_line(
+            image,
+            Pt::new(c.x - w, c.y - h),
+            Pt::new(c.x, c.y + h),
+            color,
+        );
+        draw_line(
+            image,
+            Pt::new(c.x, c.y + h),
+            Pt::new(c.x + w, c.y - h),
+            color,
+        );
+    }
+}
+impl Block for LevelBlock {}
+
+/// A cell in a [`Levels`](super::Levels) graph.
+///
+/// Sides are numbered: N, E, S, W, Up, Down
+#[derive(Clone, Debug)]
+pub struct LevelCell {
+    pub(super) id: usize,
+    pub(super) level: u32,
+    pub(super) row: u32,
+    pub(super) col: u32,
+    pub(super) x: u32,
+    pub(super) n: [Option<usize>; 6], // neighbors
+    pub(super) links: [Option<usize>; 6],
+}
+
+impl LevelCell {
+    /// Side connecting to the level above
+    pub const UP: usize = 4;
+    /// Side connecting to the level below
+    pub const DOWN: usize = 5;
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+}
+
+impl Node for LevelCell {
+    type Block = LevelBlock;
+    const N: usize = 6;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn all_neighbors(&self) -> &[Option<usize>] {
+        &self.n
+    }
+
+    fn neighbors(&self) -> crate::graphs::Neighbors {
+        crate::graphs::Neighbors::new(&self.n[..])
+    }
+
+    fn num_neighbors(&self) -> usize {
+        self.n
+            .iter()
+            .fold(0usize, |acc, n| acc + n.is_some() as usize)
+    }
+
+    fn neighbor(&self, n: usize) -> Option<usize> {
+        self.n[n]
+    }
+
+    fn neighbor_id(&self, cell: usize) -> Option<usize> {
+        self.n
+            .iter()
+            .position(|n| matches!(n, Some(n) if *n == cell))
+    }
+
+    fn links(&self) -> Neighbors {
+        Neighbors::new(&self.links)
+    }
+
+    fn linked_to(&self, id: usize) -> bool {
+        match self.neighbor_id(id) {
+            Some(n) => self.links[n].is_some(),
+            None => false,
+        }
+    }
+
+    fn linked_side(&self, n: usize) -> bool {
+        self.links[n].is_some()
+    }
+
+    fn link(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_none() {
+                    self.links[n] = Some(cell);
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyLinked(self.id, cell))
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_some() {
+                    self.links[n] = None;
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyUnlinked(self.id, cell))
+                }
+            }
+        }
+    }
+}
+
+impl crate::render::RenderBlock for LevelCell {
+    fn block(&self, height: u32, width: u32, padding: u32) -> Self::Block {
+        // `x` is the column across all levels, including the gap column between levels.
+        // the +1 accounts for borders, assuming border size of 1
+        let x = (width * self.x + self.x + padding) as f64;
+        let y = (height * self.row + self.row + padding) as f64;
+        let (w, h) = (width as f64, height as f64);
+        LevelBlock {
+            poly: Polygon::new(vec![
+                Pt::new(x, y),
+                Pt::new(x + w, y),
+                Pt::new(x + w, y + h),
+                Pt::new(x, y + h),
+            ]),
+        }
+    }
+
+    fn fill(&self, block: &Self::Block, color: &Rgba<u8>, image: &mut RgbaImage) {
+        block.poly.fill(image, *color);
+    }
+
+    fn blend_fill(
+        &self,
+        block: &Self::Block,
+        i: usize,
+        max: usize,
+        blend: &Blend,
+        image: &mut RgbaImage,
+    ) {
+        self.fill(block, &blend.color(i, max), image);
+    }
+
+    /// Unlinked up and down sides are not drawn: there are no walls between levels
+    fn edge_unlinked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if n < 4 {
+            block.poly.draw_side(n, image, *color);
+        }
+    }
+
+    /// Linked up and down sides are drawn as stairs instead of a dashed line
+    fn edge_linked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        width: u32,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if n < 4 {
+            block
+                .poly
+                .draw_dashed_side(n, width, color[3] as f32 / 255.0, image, *color);
+        } else {
+            block.draw_stairs(n, *color, image);
+        }
+    }
+
+    fn text_pos(&self, block: &Self::Block, center: bool, padding: FPt<i32>) -> FPt<u32> {
+        let pt = if center {
+            block.center().i32()
+        } else {
+            block.poly.min().i32()
+        };
+        FPt::new(pt.x + padding.x(), pt.y + padding.y()).u32()
+    }
+
+    fn arrow(
+        &self,
+        block: &Self::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            Arrow::Straight => {
+                draw_line(image, block.mid(from_n), block.mid(to_n), color);
+                for n in [from_n, to_n].into_iter().filter(|n| *n >= 4) {
+                    block.draw_level_change(n, color, image);
+                }
+            }
+            // bezier curves fall back to straight lines through the center
+            Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                self.half_arrow(block, from_n, style, color, image);
+                self.half_arrow(block, to_n, style, color, image);
+            }
+        }
+    }
+
+    fn half_arrow(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            // bezier curves fall back to straight lines
+            Arrow::Straight | Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                draw_line(image, block.mid(n), block.center(), color);
+                if n >= 4 {
+                    block.draw_level_change(n, color, image);
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,48 @@
+pub(crate) mod mask;
 pub(crate) mod nodes;
 
 use super::{Graph, Node};
 use crate::render::{RenderBlock, RenderGraph};
 use image::{Rgba, RgbaImage};
+use mask::Mask;
+
+/// How the borders of an [`Orth`] graph connect to each other.
+///
+/// Wrapping is only applied along dimensions with at least 3 cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Wrap {
+    /// No wrapping: all borders are outer walls
+    #[default]
+    None,
+    /// The left and right borders are joined (a cylinder standing upright)
+    Horizontal,
+    /// The top and bottom borders are joined (a cylinder lying on its side)
+    Vertical,
+    /// Both the left and right borders and the top and bottom borders are joined
+    Torus,
+    /// The left and right borders are joined with a half twist, so row `r` on one side meets row `height - 1 - r` on the other
+    Mobius,
+}
+
+impl Wrap {
+    /// Whether the left and right borders are joined
+    pub fn horizontal(&self) -> bool {
+        matches!(self, Self::Horizontal | Self::Torus | Self::Mobius)
+    }
+
+    /// Whether the top and bottom borders are joined
+    pub fn vertical(&self) -> bool {
+        matches!(self, Self::Vertical | Self::Torus)
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Orth<C: Node> {
     len: usize,
     height: usize,
     width: usize,
+    wrap: Wrap,
+    mask: Option<Mask>,
     cells: Vec<C>,
 }
 impl<C: Node> Orth<C> {
@@ -18,6 +52,52 @@ impl<C: Node> Orth<C> {
             false => Ok(()),
         }
     }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn wrap(&self) -> Wrap {
+        self.wrap
+    }
+
+    pub fn mask(&self) -> Option<&Mask> {
+        self.mask.as_ref()
+    }
+
+    /// Whether a cell is enabled.  Cells are only disabled in masked graphs.
+    pub fn enabled(&self, id: usize) -> bool {
+        match &self.mask {
+            Some(mask) => mask.enabled(id),
+            None => id < self.len,
+        }
+    }
+
+    /// Whether side `n` of a cell is on a seam where the graph wraps around to the other side.
+    ///
+    /// Sides are numbered: N, E, S, W
+    pub fn is_seam(&self, id: usize, n: usize) -> bool {
+        let (row, col) = (id / self.width, id % self.width);
+        match n {
+            0 => row == 0 && self.wraps_vertical(),
+            1 => col == self.width - 1 && self.wraps_horizontal(),
+            2 => row == self.height - 1 && self.wraps_vertical(),
+            3 => col == 0 && self.wraps_horizontal(),
+            _ => false,
+        }
+    }
+
+    fn wraps_horizontal(&self) -> bool {
+        self.wrap.horizontal() && self.width >= 3
+    }
+
+    fn wraps_vertical(&self) -> bool {
+        self.wrap.vertical() && self.height >= 3
+    }
 }
 impl<C: Node> Graph for Orth<C> {
     type Node = C;     
@@ -63,14 +143,17 @@ impl<C: Node> Graph for Orth<C> {
     }
 }
 
+/// Iterates over enabled cells
 pub struct Iter<'a, C: Node> {
     slice: &'a [C],
+    mask: Option<&'a Mask>,
 }
 
 impl<'a, C: Node> Iter<'a, C> {
     fn new(grid: &'a Orth<C>) -> Self {
         Self {
             slice: &grid.cells[..],
+            mask: grid.mask.as_ref(),
         }
     }
 }
@@ -81,21 +164,27 @@ impl<'a, C: Node> Iterator for Iter<'a, C> {
         match self.slice {
             [first, rest @ ..] => {
                 self.slice = rest;
-                Some(first)
+                match self.mask {
+                    Some(mask) if !mask.enabled(first.id()) => self.next(),
+                    _ => Some(first),
+                }
             }
             [] => None,
         }
     }
 }
 
+/// Iterates over enabled cells
 pub struct IterMut<'a, C: Node> {
     slice: &'a mut [C],
+    mask: Option<&'a Mask>,
 }
 
 impl<'a, C: Node> IterMut<'a, C> {
     fn new(grid: &'a mut Orth<C>) -> Self {
         Self {
             slice: &mut grid.cells[..],
+            mask: grid.mask.as_ref(),
         }
     }
 }
@@ -107,7 +196,10 @@ impl<'a, C: Node> Iterator for IterMut<'a, C> {
         match slice {
             [first, rest @ ..] => {
                 self.slice = rest;
-                Some(first)
+                match self.mask {
+                    Some(mask) if !mask.enabled(first.id()) => self.next(),
+                    _ => Some(first),
+                }
             }
             [] => None,
         }
@@ -202,4 +294,8 @@ impl<C: RenderBlock> RenderGraph for Orth<C> {
     ) {
         cell.half_arrow(block, n, style, color, image);
     }
+
+    fn seam(&self, cell: &Self::Node, n: usize) -> bool {
+        self.is_seam(cell.id(), n)
+    }
 }
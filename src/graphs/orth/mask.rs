@@ -0,0 +1,163 @@
+use crate::Error;
+use image::GrayImage;
+
+/// Marks which cells of an [`Orth`](crate::Orth) graph are enabled.
+///
+/// Disabled cells keep their ids but are excluded from [`Graph::nodes`](crate::Graph::nodes),
+/// [`Graph::random`](crate::Graph::random), neighbor lists, and rendering.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask {
+    height: usize,
+    width: usize,
+    cells: Vec<bool>,
+}
+
+impl Mask {
+    /// Create a new mask with all cells enabled
+    pub fn new(height: usize, width: usize) -> Self {
+        Self {
+            height,
+            width,
+            cells: vec![true; height * width],
+        }
+    }
+
+    /// Create a mask from a list of cells in row-major order, where `true` means the cell is enabled
+    pub fn from_bools(height: usize, width: usize, cells: &[bool]) -> Result<Self, Error> {
+        if cells.len() != height * width {
+            return Err(Error::MismatchedListSize(cells.len(), height * width));
+        }
+        Ok(Self {
+            height,
+            width,
+            cells: cells.to_vec(),
+        })
+    }
+
+    /// Create a mask from an ASCII template with one line per row.
+    ///
+    /// An `X` disables a cell, any other character enables it.  Blank lines before
+    /// and after the template are ignored, and rows shorter than the longest row are
+    /// padded with disabled cells.
+    ///
+    /// ```text
+    /// X..X
+    /// ....
+    /// X..X
+    /// ```
+    pub fn from_ascii(template: &str) -> Result<Self, Error> {
+        let lines: Vec<&str> = template.lines().collect();
+        let first = lines.iter().position(|l| !l.trim().is_empty());
+        let last = lines.iter().rposition(|l| !l.trim().is_empty());
+        let lines = match (first, last) {
+            (Some(first), Some(last)) => &lines[first..=last],
+            _ => return Err(Error::ZeroSizedGraph()),
+        };
+
+        let height = lines.len();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(height * width);
+        for line in lines {
+            let len = line.chars().count();
+            cells.extend(line.chars().map(|c| c != 'X'));
+            cells.extend(std::iter::repeat(false).take(width - len));
+        }
+        Ok(Self {
+            height,
+            width,
+            cells,
+        })
+    }
+
+    /// Create a mask from a black and white image, one cell per pixel.
+    ///
+    /// Dark pixels (below 128) disable a cell and light pixels enable it.  Use
+    /// [`Mask::invert`] for images where the shape is drawn in black.
+    pub fn from_image(image: &GrayImage) -> Self {
+        let (width, height) = image.dimensions();
+        Self {
+            height: height as usize,
+            width: width as usize,
+            cells: image.pixels().map(|p| p.0[0] >= 128).collect(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Total number of cells, including disabled cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Number of enabled cells
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|c| **c).count()
+    }
+
+    /// Whether the cell with the specified id is enabled
+    pub fn enabled(&self, id: usize) -> bool {
+        self.cells.get(id).copied().unwrap_or(false)
+    }
+
+    pub fn enabled_at(&self, row: usize, col: usize) -> bool {
+        row < self.height && col < self.width && self.cells[row * self.width + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, enabled: bool) -> Result<(), Error> {
+        if row >= self.height || col >= self.width {
+            return Err(Error::InvalidCell(row * self.width + col, self.len()));
+        }
+        self.cells[row * self.width + col] = enabled;
+        Ok(())
+    }
+
+    /// Swap enabled and disabled cells
+    pub fn invert(&mut self) {
+        self.cells.iter_mut().for_each(|c| *c = !*c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn mask_sources() {
+        let ascii = Mask::from_ascii(
+            "
+X..
+....
+X..X
+",
+        )
+        .unwrap();
+        let bools = Mask::from_bools(
+            3,
+            4,
+            &[
+                false, true, true, false, true, true, true, true, false, true, true, false,
+            ],
+        )
+        .unwrap();
+        assert_eq!(ascii, bools);
+        assert_eq!(ascii.count(), 8);
+
+        let mut image = GrayImage::from_pixel(4, 3, image::Luma([255]));
+        for (x, y) in [(0, 0), (3, 0), (0, 2), (3, 2)] {
+            image.put_pixel(x, y, image::Luma([0]));
+        }
+        assert_eq!(Mask::from_image(&image), bools);
+
+        assert!(Mask::from_bools(2, 2, &[true; 3]).is_err());
+        assert!(Mask::from_ascii("\n\n").is_err());
+    }
+}
@@ -1,34 +1,89 @@
 pub(crate) mod rect;
 
-use crate::graphs::orth::Orth;
+use crate::graphs::orth::{mask::Mask, Orth, Wrap};
 use crate::render::state::graph::{Builder, BuilderGraph};
 use image::RgbaImage;
 
 impl Orth<rect::RectCell> {
     /// Create a new rectangular grid without any links between cells
     pub fn new(height: usize, width: usize) -> Self {
+        Self::new_wrapped(height, width, Wrap::None)
+    }
+
+    /// Create a new rectangular grid with all possible cells linked
+    pub fn new_linked(height: usize, width: usize) -> Self {
         let len = height * width;
         Self {
             len,
             height,
             width,
-            cells: (0..len).map(|id| Self::new_cell(id, width, len)).collect(),
+            wrap: Wrap::None,
+            mask: None,
+            cells: (0..len)
+                .map(|id| Self::new_cell_linked(id, height, width, Wrap::None))
+                .collect(),
         }
     }
 
-    /// Create a new rectangular grid with all possible cells linked
-    pub fn new_linked(height: usize, width: usize) -> Self {
+    /// Create a new rectangular grid whose borders wrap around, without any links between cells.
+    ///
+    /// Wrapping is only applied along dimensions with at least 3 cells.
+    pub fn new_wrapped(height: usize, width: usize, wrap: Wrap) -> Self {
         let len = height * width;
         Self {
             len,
             height,
             width,
+            wrap,
+            mask: None,
             cells: (0..len)
-                .map(|id| Self::new_cell_linked(id, width, len))
+                .map(|id| Self::new_cell(id, height, width, wrap))
                 .collect(),
         }
     }
 
+    /// Create a new grid shaped by a mask, without any links between cells.
+    ///
+    /// Disabled cells have no neighbors, and the sides of enabled cells facing them
+    /// become outer walls.
+    pub fn masked(mask: Mask) -> Self {
+        let (height, width) = (mask.height(), mask.width());
+        let mut grid = Self::new(height, width);
+        for cell in grid.cells.iter_mut() {
+            if !mask.enabled(cell.id) {
+                cell.n = [None; 4];
+                continue;
+            }
+            for n in cell.n.iter_mut() {
+                if matches!(n, Some(id) if !mask.enabled(*id)) {
+                    *n = None;
+                }
+            }
+        }
+        grid.mask = Some(mask);
+        grid
+    }
+
+    /// Create a new grid where the left and right borders are joined
+    pub fn cylinder(height: usize, width: usize) -> Self {
+        Self::new_wrapped(height, width, Wrap::Horizontal)
+    }
+
+    /// Create a new grid where the top and bottom borders are joined
+    pub fn vertical_cylinder(height: usize, width: usize) -> Self {
+        Self::new_wrapped(height, width, Wrap::Vertical)
+    }
+
+    /// Create a new grid where both the left and right borders and the top and bottom borders are joined
+    pub fn torus(height: usize, width: usize) -> Self {
+        Self::new_wrapped(height, width, Wrap::Torus)
+    }
+
+    /// Create a new grid where the left and right borders are joined with a half twist
+    pub fn mobius(height: usize, width: usize) -> Self {
+        Self::new_wrapped(height, width, Wrap::Mobius)
+    }
+
     /// Use default rendering options to render an image
     pub fn render(&self) -> RgbaImage {
         self.build_render().finish().render()
@@ -67,70 +122,76 @@ impl Orth<rect::RectCell> {
         self.dist(start).shortest_path(self, end)
     }
 
-    fn above(id: usize, width: usize) -> Option<usize> {
-        if id < width {
-            None
-        } else {
+    fn above(id: usize, height: usize, width: usize, wrap: Wrap) -> Option<usize> {
+        if id >= width {
             Some(id - width)
+        } else if wrap.vertical() && height >= 3 {
+            Some(id + width * (height - 1))
+        } else {
+            None
         }
     }
 
-    fn below(id: usize, width: usize, len: usize) -> Option<usize> {
-        if id >= len - width {
-            None
-        } else {
+    fn below(id: usize, height: usize, width: usize, wrap: Wrap) -> Option<usize> {
+        if id < width * (height - 1) {
             Some(id + width)
+        } else if wrap.vertical() && height >= 3 {
+            Some(id % width)
+        } else {
+            None
         }
     }
 
-    fn left(id: usize, width: usize) -> Option<usize> {
-        if id % width == 0 {
+    fn left(id: usize, height: usize, width: usize, wrap: Wrap) -> Option<usize> {
+        if id % width != 0 {
+            Some(id - 1)
+        } else if !wrap.horizontal() || width < 3 {
             None
+        } else if wrap == Wrap::Mobius {
+            Some((height - 1 - id / width) * width + width - 1)
         } else {
-            Some(id - 1)
+            Some(id + width - 1)
         }
     }
 
-    fn right(id: usize, width: usize) -> Option<usize> {
-        if id % width == width - 1 {
+    fn right(id: usize, height: usize, width: usize, wrap: Wrap) -> Option<usize> {
+        if id % width != width - 1 {
+            Some(id + 1)
+        } else if !wrap.horizontal() || width < 3 {
             None
+        } else if wrap == Wrap::Mobius {
+            Some((height - 1 - id / width) * width)
         } else {
-            Some(id + 1)
+            Some(id + 1 - width)
         }
     }
 
-    fn new_cell(id: usize, width: usize, len: usize) -> rect::RectCell {
+    fn neighbors(id: usize, height: usize, width: usize, wrap: Wrap) -> [Option<usize>; 4] {
+        [
+            Self::above(id, height, width, wrap),
+            Self::right(id, height, width, wrap),
+            Self::below(id, height, width, wrap),
+            Self::left(id, height, width, wrap),
+        ]
+    }
+
+    fn new_cell(id: usize, height: usize, width: usize, wrap: Wrap) -> rect::RectCell {
         rect::RectCell {
             id,
             row: id as u32 / width as u32,
             col: id as u32 % width as u32,
-            n: [
-                Self::above(id, width),
-                Self::right(id, width),
-                Self::below(id, width, len),
-                Self::left(id, width),
-            ],
+            n: Self::neighbors(id, height, width, wrap),
             links: [None; 4],
         }
     }
 
-    fn new_cell_linked(id: usize, width: usize, len: usize) -> rect::RectCell {
+    fn new_cell_linked(id: usize, height: usize, width: usize, wrap: Wrap) -> rect::RectCell {
         rect::RectCell {
             id,
             row: id as u32 / width as u32,
             col: id as u32 % width as u32,
-            n: [
-                Self::above(id, width),
-                Self::right(id, width),
-                Self::below(id, width, len),
-                Self::left(id, width),
-            ],
-            links: [
-                Self::above(id, width),
-                Self::right(id, width),
-                Self::below(id, width, len),
-                Self::left(id, width),
-            ],
+            n: Self::neighbors(id, height, width, wrap),
+            links: Self::neighbors(id, height, width, wrap),
         }
     }
 }
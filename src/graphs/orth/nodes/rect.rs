@@ -406,6 +406,79 @@ mod tests {
         assert_eq!(grid.cells[8].n, [Some(5), None, None, Some(7)]);
     }
 
+    #[test]
+    fn test_wrapped_neighbors() {
+        crate::logger(log::LevelFilter::Warn);
+        use crate::graphs::Graph;
+        let grid: crate::Orth<RectCell> = crate::Orth::torus(3, 3);
+        assert_eq!(grid.cells[0].n, [Some(6), Some(1), Some(3), Some(2)]);
+        assert_eq!(grid.cells[8].n, [Some(5), Some(6), Some(2), Some(7)]);
+        assert!(grid.is_seam(0, 0) && grid.is_seam(0, 3));
+        assert!(!grid.is_seam(4, 0));
+
+        let grid: crate::Orth<RectCell> = crate::Orth::mobius(3, 3);
+        assert_eq!(grid.cells[0].n, [None, Some(1), Some(3), Some(8)]);
+        assert_eq!(grid.cells[5].n, [Some(2), Some(3), Some(8), Some(4)]);
+        assert!(!grid.is_seam(0, 0));
+
+        // wrapping needs at least 3 cells along a dimension
+        let grid: crate::Orth<RectCell> = crate::Orth::cylinder(2, 2);
+        assert_eq!(grid.cells[0].n, [None, Some(1), Some(2), None]);
+
+        for grid in [
+            crate::Orth::cylinder(4, 5),
+            crate::Orth::vertical_cylinder(4, 5),
+            crate::Orth::torus(4, 5),
+            crate::Orth::mobius(4, 5),
+        ] {
+            for cell in grid.nodes() {
+                for n in cell.neighbors() {
+                    assert!(grid.node(*n).neighbor_id(cell.id()).is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn masked_image() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        use crate::graphs::Graph;
+        use rand::SeedableRng;
+        let rng = &mut rand_xoshiro::SplitMix64::seed_from_u64(13131313131313131313);
+        let mask = crate::Mask::from_ascii(
+            "
+XX....XX
+X......X
+..XXXX..
+..X.....
+..XXXX..
+X......X
+XX....XX
+",
+        )
+        .unwrap();
+        let mut grid = crate::Orth::masked(mask);
+        assert_eq!(grid.node_ids().len(), 35);
+        assert!(!grid.node_ids().contains(&0));
+        assert_eq!(grid.cells[2].n, [None, Some(3), Some(10), None]);
+        assert_eq!(grid.cells[0].n, [None; 4]);
+        assert!(grid.link(1, 2).is_err());
+
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        assert!(grid.nodes().all(|c| !c.is_empty()));
+        grid.render().save("images/tests/rect_masked.png")
+    }
+
+    #[test]
+    fn torus_image() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        use rand::SeedableRng;
+        let rng = &mut rand_xoshiro::SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = crate::Orth::torus(6, 6);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        grid.render().save("images/tests/rect_torus.png")
+    }
+
     #[test]
     fn rect_image() -> Result<(), image::ImageError> {
         crate::logger(crate::LOG_LEVEL);
@@ -0,0 +1,389 @@
+pub(crate) mod cell;
+
+use super::{Graph, Node};
+use crate::drawing::cir::AnnulusSlice;
+use crate::render::state::graph::{Builder, BuilderGraph};
+use crate::render::RenderGraph;
+use cell::{PolarBlock, PolarCell};
+use image::{Rgba, RgbaImage};
+use std::f64::consts::PI;
+
+/// A circular (theta) graph made of concentric rings.
+///
+/// Ring 0 is a single center cell.  Each ring further out is subdivided so
+/// that cells keep roughly the same width as the rings get larger.
+#[derive(Clone, Debug)]
+pub struct Polar {
+    len: usize,
+    /// Number of cells in each ring
+    rings: Vec<usize>,
+    cells: Vec<PolarCell>,
+}
+
+impl Polar {
+    /// Create a new polar grid without any links between cells
+    pub fn new(rings: usize) -> Self {
+        let counts = Self::ring_counts(rings);
+        let offsets: Vec<usize> = counts
+            .iter()
+            .scan(0, |acc, c| {
+                let offset = *acc;
+                *acc += c;
+                Some(offset)
+            })
+            .collect();
+        let len = counts.iter().sum();
+        let mut cells = Vec::with_capacity(len);
+
+        for (row, count) in counts.iter().enumerate() {
+            for col in 0..*count {
+                let id = offsets[row] + col;
+                let mut n = smallvec::SmallVec::new();
+                if row == 0 {
+                    n.extend([None, None, None]);
+                } else {
+                    let ratio = count / counts[row - 1];
+                    n.push(Some(offsets[row - 1] + col / ratio));
+                    n.push(Some(offsets[row] + (col + count - 1) % count));
+                    n.push(Some(offsets[row] + (col + 1) % count));
+                }
+                if row + 1 < counts.len() {
+                    let ratio = counts[row + 1] / count;
+                    n.extend((0..ratio).map(|k| Some(offsets[row + 1] + col * ratio + k)));
+                } else {
+                    n.push(None);
+                }
+                let links = n.iter().map(|_| None).collect();
+                cells.push(PolarCell {
+                    id,
+                    row: row as u32,
+                    col: col as u32,
+                    n,
+                    links,
+                });
+            }
+        }
+
+        Self {
+            len,
+            rings: counts,
+            cells,
+        }
+    }
+
+    /// Number of cells in each ring.  Each ring has a whole multiple of the number of cells in the previous ring.
+    fn ring_counts(rings: usize) -> Vec<usize> {
+        let mut counts: Vec<usize> = Vec::with_capacity(rings);
+        if rings == 0 {
+            return counts;
+        }
+        counts.push(1);
+        let height = 1.0 / rings as f64;
+        for row in 1..rings {
+            let radius = row as f64 / rings as f64;
+            let circumference = 2.0 * PI * radius;
+            let prev = counts[row - 1];
+            let width = circumference / prev as f64;
+            let ratio = ((width / height).round() as usize).max(1);
+            counts.push(prev * ratio);
+        }
+        counts
+    }
+
+    /// Number of rings, including the center cell
+    pub fn rings(&self) -> usize {
+        self.rings.len()
+    }
+
+    /// Number of cells in the specified ring
+    pub fn ring_len(&self, ring: usize) -> Option<usize> {
+        self.rings.get(ring).copied()
+    }
+
+    /// Use default rendering options to render an image
+    pub fn render(&self) -> RgbaImage {
+        self.build_render().finish().render()
+    }
+
+    /// Customize the renderer using a reference to the current graph (preferred)
+    pub fn build_render(&self) -> BuilderGraph<'_, Self> {
+        Builder::graph(self)
+    }
+
+    /// Customize the renderer using the current owned graph
+    pub fn build_render_owned<'g>(self) -> BuilderGraph<'g, Self> {
+        Builder::owned_graph(self)
+    }
+
+    /// Create a distance map from the current graph and a given starting point
+    pub fn dist(&self, start: usize) -> crate::Dist {
+        crate::Dist::simple(self, start)
+    }
+
+    /// Find the shortest path using a given distance map
+    pub fn shortest_path(
+        &self,
+        dist: &crate::Dist,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        dist.shortest_path(self, end)
+    }
+
+    /// Find the shortest path using a weight of 1 for all edges
+    pub fn shortest_path_simple(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        self.dist(start).shortest_path(self, end)
+    }
+
+    fn check_id(&self, cell: usize) -> Result<(), crate::Error> {
+        match cell >= self.len {
+            true => Err(crate::Error::InvalidCell(cell, self.len)),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Graph for Polar {
+    type Node = PolarCell;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn node(&self, id: usize) -> &Self::Node {
+        &self.cells[id]
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Self::Node {
+        &mut self.cells[id]
+    }
+
+    fn nodes(&self) -> Box<dyn Iterator<Item = &Self::Node> + '_> {
+        Box::new(self.cells.iter())
+    }
+
+    fn nodes_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Node> + '_> {
+        Box::new(self.cells.iter_mut())
+    }
+
+    fn link(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| self.cells[a].link(b).and_then(|_| self.cells[b].link(a)))
+    }
+
+    fn unlink(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| {
+                self.cells[a]
+                    .unlink(b)
+                    .and_then(|_| self.cells[b].unlink(a))
+            })
+    }
+}
+
+/// Rings are `block_height` thick; `block_width` is not used.
+impl RenderGraph for Polar {
+    fn size(&self, block_height: u32, _block_width: u32, padding: u32) -> (u32, u32) {
+        // the +1 accounts for borders, assuming border size of 1
+        let d = 2 * self.rings() as u32 * (block_height + 1) + 1 + padding + padding;
+        (d, d)
+    }
+
+    fn blocks(&self, height: u32, _width: u32, padding: u32) -> Vec<PolarBlock> {
+        let thickness = (height + 1) as f64;
+        let radius = self.rings() as f64 * thickness;
+        let center = (padding as f64 + radius, padding as f64 + radius);
+        self.cells
+            .iter()
+            .map(|c| {
+                let theta = PI * 2.0 / self.rings[c.row as usize] as f64;
+                let slice = AnnulusSlice::new(
+                    center,
+                    theta * (c.col + 1) as f64,
+                    theta * c.col as f64,
+                    thickness * c.row as f64,
+                    thickness * (c.row + 1) as f64,
+                );
+                PolarBlock::new(slice, c.num_outward(), c.row == 0)
+            })
+            .collect()
+    }
+
+    fn fill(&self, _cell: &PolarCell, block: &PolarBlock, color: &Rgba<u8>, img: &mut RgbaImage) {
+        block.slice().fill(img, *color);
+    }
+
+    fn blend_fill(
+        &self,
+        cell: &PolarCell,
+        block: &PolarBlock,
+        i: usize,
+        max: usize,
+        blend: &crate::render::opts::blend::Blend,
+        image: &mut RgbaImage,
+    ) {
+        self.fill(cell, block, &blend.color(i, max), image);
+    }
+
+    /// Polar cells have no corner to align text to so text is always positioned relative to the center
+    fn text_pos(
+        &self,
+        _cell: &PolarCell,
+        block: &PolarBlock,
+        _center: bool,
+        padding: freehand::Pt<i32>,
+    ) -> freehand::Pt<u32> {
+        let center = block.center().i32();
+        freehand::Pt::new(center.x + padding.x(), center.y + padding.y()).u32()
+    }
+
+    fn edge(
+        &self,
+        cell: &PolarCell,
+        block: &PolarBlock,
+        n: usize,
+        dash_width: u32,
+        unlinked_color: &Rgba<u8>,
+        linked_color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if !cell.linked_side(n) {
+            block.draw_side(n, image, *unlinked_color);
+        } else if dash_width != 0 {
+            block.draw_dashed_side(
+                n,
+                dash_width,
+                linked_color[3] as f32 / 255.0,
+                image,
+                *linked_color,
+            );
+        }
+    }
+
+    fn arrow(
+        &self,
+        cell: &PolarCell,
+        block: &PolarBlock,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            Arrow::Straight => {
+                let (a, b) = (block.mid(from_n), block.mid(to_n));
+                imageproc::drawing::draw_line_segment_mut(
+                    image,
+                    (a.x as f32, a.y as f32),
+                    (b.x as f32, b.y as f32),
+                    color,
+                )
+            }
+            // bezier curves fall back to straight lines through the center
+            Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                self.half_arrow(cell, block, from_n, style, color, image);
+                self.half_arrow(cell, block, to_n, style, color, image);
+            }
+        }
+    }
+
+    fn half_arrow(
+        &self,
+        _cell: &PolarCell,
+        block: &PolarBlock,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            // bezier curves fall back to straight lines
+            Arrow::Straight | Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                let (a, b) = (block.mid(n), block.center());
+                imageproc::drawing::draw_line_segment_mut(
+                    image,
+                    (a.x as f32, a.y as f32),
+                    (b.x as f32, b.y as f32),
+                    color,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn polar_neighbors() {
+        crate::logger(log::LevelFilter::Warn);
+        let grid = Polar::new(4);
+        assert_eq!(grid.rings, vec![1, 6, 12, 24]);
+        assert_eq!(grid.len(), 43);
+        assert_eq!(grid.cells[0].n.len(), 3 + 6);
+        assert_eq!(
+            grid.cells[1].n[..],
+            [Some(0), Some(6), Some(2), Some(7), Some(8)]
+        );
+        assert_eq!(
+            grid.cells[7].n[..],
+            [Some(1), Some(18), Some(8), Some(19), Some(20)]
+        );
+        assert_eq!(grid.cells[42].n[..], [Some(18), Some(41), Some(19), None]);
+        for cell in grid.nodes() {
+            for n in cell.neighbors() {
+                assert!(grid.node(*n).neighbor_id(cell.id()).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn polar_path() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Polar::new(6);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        grid.render().save("images/tests/polar_grid.png")?;
+
+        let dist = grid.dist(0);
+        let path = grid.shortest_path(&dist, grid.len() - 1).unwrap();
+        assert_eq!(path.step(0), 0);
+        let renderer = grid.build_render().finish();
+        let path_renderer = crate::render::state::path::Builder::render_state(&renderer)
+            .default_opts()
+            .owned_path(path)
+            .finish();
+        path_renderer.render().save("images/tests/polar_path.png")
+    }
+
+    #[test]
+    fn polar_bezier_path() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Polar::new(4);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let path = grid.shortest_path_simple(0, grid.len() - 1).unwrap();
+        let opts = crate::render::opts::PathOpts::build()
+            .arrow_style(crate::render::opts::Arrow::BezierQuad(0.25, 0.75))
+            .build();
+        let renderer = grid.build_render().finish();
+        crate::render::state::path::Builder::render_state(&renderer)
+            .owned_opts(opts)
+            .owned_path(path)
+            .finish()
+            .render();
+    }
+}
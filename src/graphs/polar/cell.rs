@@ -0,0 +1,203 @@
+use crate::drawing::cir::AnnulusSlice;
+use crate::drawing::Pt;
+use crate::graphs::{Block, Neighbors, Node};
+use crate::Error;
+use crate::DEFAULT_NEIGHBORS;
+use image::{Rgba, RgbaImage};
+use smallvec::SmallVec;
+
+/// Side of a cell facing the center of the graph
+pub(crate) const INWARD: usize = 0;
+/// Side of a cell along its clockwise radial line
+pub(crate) const CW: usize = 1;
+/// Side of a cell along its counter-clockwise radial line
+pub(crate) const CCW: usize = 2;
+/// First side facing away from the center.  A cell has one outward side for each cell in the next ring that it borders.
+pub(crate) const OUTWARD: usize = 3;
+
+#[derive(Clone, Debug)]
+pub struct PolarBlock {
+    slice: AnnulusSlice,
+    /// Number of outward sides the outer arc is divided into
+    outward: usize,
+    /// The center cell is a full circle without radial lines or an inner arc
+    center: bool,
+}
+
+impl PolarBlock {
+    pub(super) fn new(slice: AnnulusSlice, outward: usize, center: bool) -> Self {
+        Self {
+            slice,
+            outward: outward.max(1),
+            center,
+        }
+    }
+
+    pub(crate) fn slice(&self) -> &AnnulusSlice {
+        &self.slice
+    }
+
+    pub(crate) fn center(&self) -> Pt<f64> {
+        if self.center {
+            self.slice.origin().into()
+        } else {
+            self.slice.center().into()
+        }
+    }
+
+    pub(crate) fn mid(&self, n: usize) -> Pt<f64> {
+        match n {
+            INWARD => self.slice.mid_inner(),
+            CW => self.slice.mid_cw(),
+            CCW => self.slice.mid_ccw(),
+            _ => self.slice.mid_outer(n - OUTWARD, self.outward),
+        }
+    }
+
+    pub(crate) fn draw_side(&self, n: usize, image: &mut RgbaImage, color: Rgba<u8>) {
+        match n {
+            _ if self.center && n < OUTWARD => {}
+            INWARD => self.slice.draw_inner(image, color),
+            CW => self.slice.draw_cw(image, color),
+            CCW => self.slice.draw_ccw(image, color),
+            _ => self
+                .slice
+                .draw_outer(n - OUTWARD, self.outward, image, color),
+        }
+    }
+
+    pub(crate) fn draw_dashed_side(
+        &self,
+        n: usize,
+        width: u32,
+        opacity: f32,
+        image: &mut RgbaImage,
+        color: Rgba<u8>,
+    ) {
+        match n {
+            _ if self.center && n < OUTWARD => {}
+            INWARD => self.slice.draw_dashed_inner(width, opacity, image, color),
+            CW => self.slice.draw_dashed_cw(width, opacity, image, color),
+            CCW => self.slice.draw_dashed_ccw(width, opacity, image, color),
+            _ => self.slice.draw_dashed_outer(
+                n - OUTWARD,
+                self.outward,
+                width,
+                opacity,
+                image,
+                color,
+            ),
+        }
+    }
+}
+impl Block for PolarBlock {}
+
+/// A cell in a [`Polar`](super::Polar) graph.
+///
+/// Neighbors are ordered: inward, clockwise, counter-clockwise, followed by
+/// one or more outward neighbors (in clockwise to counter-clockwise order).
+#[derive(Clone, Debug)]
+pub struct PolarCell {
+    pub(super) id: usize,
+    /// The ring the cell is in, with ring 0 being the center cell
+    pub(super) row: u32,
+    /// Position of the cell within its ring
+    pub(super) col: u32,
+    pub(super) n: SmallVec<[Option<usize>; DEFAULT_NEIGHBORS]>, // neighbors
+    pub(super) links: SmallVec<[Option<usize>; DEFAULT_NEIGHBORS]>,
+}
+
+impl PolarCell {
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    /// Number of outward sides the cell has (the outermost ring has a single outward side with no neighbor)
+    pub(crate) fn num_outward(&self) -> usize {
+        self.n.len() - OUTWARD
+    }
+}
+
+impl Node for PolarCell {
+    type Block = PolarBlock;
+    const N: usize = 5;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn all_neighbors(&self) -> &[Option<usize>] {
+        &self.n
+    }
+
+    fn neighbors(&self) -> Neighbors {
+        Neighbors::new(&self.n[..])
+    }
+
+    fn num_neighbors(&self) -> usize {
+        self.n
+            .iter()
+            .fold(0usize, |acc, n| acc + n.is_some() as usize)
+    }
+
+    fn max_neighbors(&self) -> usize {
+        self.n.len()
+    }
+
+    fn neighbor(&self, n: usize) -> Option<usize> {
+        self.n.get(n).copied().flatten()
+    }
+
+    fn neighbor_id(&self, cell: usize) -> Option<usize> {
+        self.n
+            .iter()
+            .position(|n| matches!(n, Some(n) if *n == cell))
+    }
+
+    fn links(&self) -> Neighbors {
+        Neighbors::new(&self.links)
+    }
+
+    fn linked_to(&self, id: usize) -> bool {
+        match self.neighbor_id(id) {
+            Some(n) => self.links[n].is_some(),
+            None => false,
+        }
+    }
+
+    fn linked_side(&self, n: usize) -> bool {
+        matches!(self.links.get(n), Some(Some(_)))
+    }
+
+    fn link(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_none() {
+                    self.links[n] = Some(cell);
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyLinked(self.id, cell))
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_some() {
+                    self.links[n] = None;
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyUnlinked(self.id, cell))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::Block;
+use crate::drawing::poly::{draw_line, Polygon};
+use crate::drawing::Pt;
+use crate::render::opts::blend::Blend;
+use image::{Rgba, RgbaImage};
+
+/// A block for any cell shaped like a polygon, where side `n` of the polygon is the edge shared with neighbor `n`.
+#[derive(Clone, Debug)]
+pub struct PolyBlock {
+    poly: Polygon,
+}
+
+impl PolyBlock {
+    pub(crate) fn new(poly: Polygon) -> Self {
+        Self { poly }
+    }
+
+    pub(crate) fn poly(&self) -> &Polygon {
+        &self.poly
+    }
+
+    pub(crate) fn center(&self) -> Pt<f64> {
+        self.poly.center()
+    }
+
+    /// Midpoint of the specified edge
+    pub(crate) fn mid(&self, n: usize) -> Pt<f64> {
+        if n >= self.poly.len() {
+            panic!("Invalid edge {}", n);
+        }
+        self.poly.mid(n)
+    }
+
+    pub(crate) fn fill(&self, color: &Rgba<u8>, image: &mut RgbaImage) {
+        self.poly.fill(image, *color);
+    }
+
+    pub(crate) fn blend_fill(&self, i: usize, max: usize, blend: &Blend, image: &mut RgbaImage) {
+        self.fill(&blend.color(i, max), image);
+    }
+
+    pub(crate) fn draw_side(&self, n: usize, color: &Rgba<u8>, image: &mut RgbaImage) {
+        self.poly.draw_side(n, image, *color);
+    }
+
+    pub(crate) fn draw_dashed_side(
+        &self,
+        n: usize,
+        width: u32,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        self.poly
+            .draw_dashed_side(n, width, color[3] as f32 / 255.0, image, *color);
+    }
+
+    pub(crate) fn text_pos(&self, center: bool, padding: freehand::Pt<i32>) -> freehand::Pt<u32> {
+        let pt = if center {
+            self.center().i32()
+        } else {
+            self.poly.min().i32()
+        };
+        freehand::Pt::new(pt.x + padding.x(), pt.y + padding.y()).u32()
+    }
+
+    pub(crate) fn arrow(
+        &self,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            Arrow::Straight => draw_line(image, self.mid(from_n), self.mid(to_n), color),
+            // bezier curves fall back to straight lines through the center
+            Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                self.half_arrow(from_n, style, color, image);
+                self.half_arrow(to_n, style, color, image);
+            }
+        }
+    }
+
+    pub(crate) fn half_arrow(
+        &self,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            // bezier curves fall back to straight lines
+            Arrow::Straight | Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                draw_line(image, self.mid(n), self.center(), color)
+            }
+        }
+    }
+}
+impl Block for PolyBlock {}
@@ -0,0 +1,472 @@
+pub(crate) mod node;
+
+use super::{Graph, Node};
+use crate::drawing::Pt;
+use crate::render::state::graph::{Builder, BuilderGraph};
+use crate::render::RenderGraph;
+use image::{Rgba, RgbaImage};
+use node::{SparseBlock, SparseNode};
+
+/// A graph with an arbitrary layout, where each node has a 2D position and an explicit list of neighbors.
+///
+/// Positions are measured in blocks: when rendered, a distance of 1 on the x axis
+/// is `block_width` pixels and a distance of 1 on the y axis is `block_height` pixels.
+///
+/// Edges are drawn between the centers of neighbors rather than as walls: a link is a solid line in
+/// the dashed edge color, the color other graphs use for linked sides, and neighbors without a link
+/// are joined by a dashed line in the inner edge color.
+#[derive(Clone, Debug, Default)]
+pub struct SparseGraph {
+    nodes: Vec<SparseNode>,
+    /// Distance between the two furthest apart neighbors
+    longest: f64,
+}
+
+impl SparseGraph {
+    /// Create an empty graph
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            longest: 0.0,
+        }
+    }
+
+    pub(crate) fn from_nodes(nodes: Vec<SparseNode>) -> Self {
+        let mut graph = Self {
+            nodes,
+            longest: 0.0,
+        };
+        for node in graph.nodes.iter() {
+            for n in node.neighbors() {
+                graph.longest = graph.longest.max(graph.distance(node.id, *n));
+            }
+        }
+        graph
+    }
+
+    /// Create a graph from a list of node positions and the pairs of nodes that are neighbors
+    pub fn from_edges(
+        positions: &[Pt<f64>],
+        edges: &[(usize, usize)],
+    ) -> Result<Self, crate::Error> {
+        let mut graph = Self::new();
+        positions.iter().for_each(|pos| {
+            graph.add_node(*pos);
+        });
+        for (a, b) in edges {
+            graph.add_neighbor(*a, *b)?;
+        }
+        Ok(graph)
+    }
+
+    /// Add a node with no neighbors, returning its id
+    pub fn add_node(&mut self, pos: Pt<f64>) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(SparseNode::new(id, pos));
+        id
+    }
+
+    /// Make two nodes neighbors so they may be linked later
+    pub fn add_neighbor(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a).and_then(|_| self.check_id(b))?;
+        if a == b {
+            return Err(crate::Error::InvalidNeighbor(a, b));
+        }
+        if self.nodes[a].neighbor_id(b).is_some() {
+            return Err(crate::Error::DuplicateNeighbor(a, b));
+        }
+        self.nodes[a].n.push(Some(b));
+        self.nodes[a].links.push(None);
+        self.nodes[b].n.push(Some(a));
+        self.nodes[b].links.push(None);
+        self.longest = self.longest.max(self.distance(a, b));
+        Ok(())
+    }
+
+    pub fn pos(&self, id: usize) -> Pt<f64> {
+        self.nodes[id].pos
+    }
+
+    /// Straight line distance between the positions of two nodes
+    pub fn distance(&self, a: usize, b: usize) -> f64 {
+        let d = self.pos(a) - self.pos(b);
+        d.x.hypot(d.y)
+    }
+
+    /// Distance between the two furthest apart neighbors, or 0 when there are no neighbors
+    pub fn longest_edge(&self) -> f64 {
+        self.longest
+    }
+
+    /// Top left and bottom right corners of the bounding box around all node positions
+    pub fn bounds(&self) -> (Pt<f64>, Pt<f64>) {
+        if self.nodes.is_empty() {
+            return (Pt::new(0.0, 0.0), Pt::new(0.0, 0.0));
+        }
+        self.nodes.iter().fold(
+            (
+                Pt::new(f64::INFINITY, f64::INFINITY),
+                Pt::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), n| {
+                (
+                    Pt::new(min.x.min(n.pos.x), min.y.min(n.pos.y)),
+                    Pt::new(max.x.max(n.pos.x), max.y.max(n.pos.y)),
+                )
+            },
+        )
+    }
+
+    /// Use default rendering options to render an image
+    pub fn render(&self) -> RgbaImage {
+        self.build_render().finish().render()
+    }
+
+    /// Customize the renderer using a reference to the current graph (preferred)
+    pub fn build_render(&self) -> BuilderGraph<'_, Self> {
+        Builder::graph(self)
+    }
+
+    /// Customize the renderer using the current owned graph
+    pub fn build_render_owned<'g>(self) -> BuilderGraph<'g, Self> {
+        Builder::owned_graph(self)
+    }
+
+    /// Create a distance map from the current graph and a given starting point
+    pub fn dist(&self, start: usize) -> crate::Dist {
+        crate::Dist::simple(self, start)
+    }
+
+    /// Find the shortest path using a given distance map
+    pub fn shortest_path(
+        &self,
+        dist: &crate::Dist,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        dist.shortest_path(self, end)
+    }
+
+    /// Find the shortest path using a weight of 1 for all edges
+    pub fn shortest_path_simple(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        self.dist(start).shortest_path(self, end)
+    }
+
+    /// Pixel position of a node, given the top left corner of the bounding box
+    pub(crate) fn pixel(
+        pos: Pt<f64>,
+        min: Pt<f64>,
+        height: u32,
+        width: u32,
+        padding: u32,
+    ) -> Pt<f64> {
+        let (h, w) = (height as f64, width as f64);
+        Pt::new(
+            (pos.x - min.x) * w + w / 2.0 + padding as f64,
+            (pos.y - min.y) * h + h / 2.0 + padding as f64,
+        )
+    }
+
+    fn check_id(&self, cell: usize) -> Result<(), crate::Error> {
+        match cell >= self.nodes.len() {
+            true => Err(crate::Error::InvalidCell(cell, self.nodes.len())),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Graph for SparseGraph {
+    type Node = SparseNode;
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn node(&self, id: usize) -> &Self::Node {
+        &self.nodes[id]
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Self::Node {
+        &mut self.nodes[id]
+    }
+
+    fn nodes(&self) -> Box<dyn Iterator<Item = &Self::Node> + '_> {
+        Box::new(self.nodes.iter())
+    }
+
+    fn nodes_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Node> + '_> {
+        Box::new(self.nodes.iter_mut())
+    }
+
+    fn link(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| self.nodes[a].link(b).and_then(|_| self.nodes[b].link(a)))
+    }
+
+    fn unlink(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| {
+                self.nodes[a]
+                    .unlink(b)
+                    .and_then(|_| self.nodes[b].unlink(a))
+            })
+    }
+}
+
+/// Nodes are drawn as circles and links as line segments between them.
+///
+/// Unlike graphs made of cells, links are drawn solid with the edge color, while
+/// neighbors without a link are drawn dashed (or not at all if the dash width is 0).
+impl RenderGraph for SparseGraph {
+    fn size(&self, block_height: u32, block_width: u32, padding: u32) -> (u32, u32) {
+        let (min, max) = self.bounds();
+        let (h, w) = (block_height as f64, block_width as f64);
+        (
+            ((max.x - min.x) * w + w).ceil() as u32 + 1 + padding + padding,
+            ((max.y - min.y) * h + h).ceil() as u32 + 1 + padding + padding,
+        )
+    }
+
+    fn blocks(&self, height: u32, width: u32, padding: u32) -> Vec<SparseBlock> {
+        let radius = height.min(width) as f64 / 4.0;
+        let (min, _) = self.bounds();
+        let pixel = |pos: Pt<f64>| Self::pixel(pos, min, height, width, padding);
+        self.nodes
+            .iter()
+            .map(|n| SparseBlock {
+                center: pixel(n.pos),
+                radius,
+                ends: n
+                    .n
+                    .iter()
+                    .map(|o| match o {
+                        Some(o) => pixel(self.nodes[*o].pos),
+                        None => pixel(n.pos),
+                    })
+                    .collect(),
+                cell: None,
+            })
+            .collect()
+    }
+
+    fn fill(&self, _cell: &SparseNode, block: &SparseBlock, color: &Rgba<u8>, img: &mut RgbaImage) {
+        let c = block.center().i32();
+        imageproc::drawing::draw_filled_circle_mut(
+            img,
+            (c.x, c.y),
+            block.radius().round() as i32,
+            *color,
+        );
+    }
+
+    fn blend_fill(
+        &self,
+        cell: &SparseNode,
+        block: &SparseBlock,
+        i: usize,
+        max: usize,
+        blend: &crate::render::opts::blend::Blend,
+        image: &mut RgbaImage,
+    ) {
+        self.fill(cell, block, &blend.color(i, max), image);
+    }
+
+    /// Nodes have no corner to align text to so text is always positioned relative to the center
+    fn text_pos(
+        &self,
+        _cell: &SparseNode,
+        block: &SparseBlock,
+        _center: bool,
+        padding: freehand::Pt<i32>,
+    ) -> freehand::Pt<u32> {
+        let center = block.center().i32();
+        freehand::Pt::new(center.x + padding.x(), center.y + padding.y()).u32()
+    }
+
+    /// Outlines the circle of every node, including nodes without any edges
+    fn outline(
+        &self,
+        _cell: &SparseNode,
+        block: &SparseBlock,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        let c = block.center().i32();
+        imageproc::drawing::draw_hollow_circle_mut(
+            image,
+            (c.x, c.y),
+            block.radius().round() as i32,
+            *color,
+        );
+    }
+
+    fn edge(
+        &self,
+        cell: &SparseNode,
+        block: &SparseBlock,
+        n: usize,
+        dash_width: u32,
+        unlinked_color: &Rgba<u8>,
+        linked_color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if cell.neighbor(n).is_none() {
+            return;
+        }
+        let (a, b) = block.segment(n);
+        if cell.linked_side(n) {
+            crate::drawing::poly::draw_line(image, a, b, *linked_color);
+        } else if dash_width != 0 {
+            crate::drawing::draw_dashed(
+                &crate::drawing::line_points(a, b),
+                dash_width,
+                unlinked_color[3] as f32 / 255.0,
+                image,
+                *unlinked_color,
+            );
+        }
+    }
+
+    fn arrow(
+        &self,
+        cell: &SparseNode,
+        block: &SparseBlock,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            Arrow::Straight => {
+                crate::drawing::poly::draw_line(image, block.mid(from_n), block.mid(to_n), color)
+            }
+            // bezier curves fall back to straight lines through the center
+            Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                self.half_arrow(cell, block, from_n, style, color, image);
+                self.half_arrow(cell, block, to_n, style, color, image);
+            }
+        }
+    }
+
+    fn half_arrow(
+        &self,
+        _cell: &SparseNode,
+        block: &SparseBlock,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            // bezier curves fall back to straight lines
+            Arrow::Straight | Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                crate::drawing::poly::draw_line(image, block.mid(n), block.center(), color)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    /// A small road network: two rings of nodes joined by spokes
+    fn roads() -> SparseGraph {
+        let mut graph = SparseGraph::new();
+        for ring in 1..=2 {
+            for i in 0..6 {
+                let theta = std::f64::consts::PI / 3.0 * i as f64;
+                let r = ring as f64 * 1.5;
+                graph.add_node(Pt::new(r * theta.cos(), r * theta.sin()));
+            }
+        }
+        for i in 0..6 {
+            graph.add_neighbor(i, (i + 1) % 6).unwrap();
+            graph.add_neighbor(6 + i, 6 + (i + 1) % 6).unwrap();
+            graph.add_neighbor(i, 6 + i).unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn sparse_neighbors() {
+        crate::logger(log::LevelFilter::Warn);
+        let graph = roads();
+        assert_eq!(graph.len(), 12);
+        assert_eq!(graph.node(0).all_neighbors(), &[Some(1), Some(6), Some(5)]);
+        assert_eq!(graph.node(6).max_neighbors(), 3);
+        let edges = crate::edges::Undirected::new(&graph, 0, 0);
+        assert_eq!(edges.edges().len(), 18);
+        assert!(edges.outer().is_empty());
+
+        let mut graph = graph;
+        assert!(graph.add_neighbor(0, 1).is_err());
+        assert!(graph.add_neighbor(0, 0).is_err());
+        assert!(graph.link(0, 2).is_err());
+        assert!(SparseGraph::from_edges(&[Pt::new(0.0, 0.0)], &[(0, 1)]).is_err());
+    }
+
+    #[test]
+    fn sparse_outline() {
+        crate::logger(log::LevelFilter::Warn);
+        let mut graph = roads();
+        let isolated = graph.add_node(Pt::new(0.0, 4.0));
+        let state = graph.build_render().finish();
+        let image = state.render();
+        // the isolated node and the highest id node with edges both own no edges
+        for id in [11, isolated] {
+            let block = &state.blocks()[id];
+            let c = block.center().i32();
+            let x = c.x + block.radius().round() as i32;
+            assert_eq!(
+                image.get_pixel(x as u32, c.y as u32),
+                state.opts().colors().edges()
+            );
+        }
+    }
+
+    #[test]
+    fn sparse_path() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = roads();
+        crate::algo::hk::huntkill(&mut graph, rng).unwrap();
+        let path = graph.shortest_path_simple(0, 9).unwrap();
+        let renderer = graph.build_render().finish();
+        let path_renderer = crate::render::state::path::Builder::render_state(&renderer)
+            .default_opts()
+            .owned_path(path)
+            .finish();
+        path_renderer.render().save("images/tests/sparse_path.png")
+    }
+
+    #[test]
+    fn sparse_bezier_path() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut graph = roads();
+        crate::algo::hk::huntkill(&mut graph, rng).unwrap();
+        let path = graph.shortest_path_simple(0, 9).unwrap();
+        let opts = crate::render::opts::PathOpts::build()
+            .arrow_style(crate::render::opts::Arrow::BezierQuad(0.25, 0.75))
+            .build();
+        let renderer = graph.build_render().finish();
+        crate::render::state::path::Builder::render_state(&renderer)
+            .owned_opts(opts)
+            .owned_path(path)
+            .finish()
+            .render();
+    }
+}
@@ -0,0 +1,159 @@
+use crate::drawing::Pt;
+use crate::graphs::poly::PolyBlock;
+use crate::graphs::{Block, Neighbors, Node};
+use crate::Error;
+use crate::DEFAULT_NEIGHBORS;
+use smallvec::SmallVec;
+
+/// A node drawn as a circle, with links drawn as line segments to its neighbors
+#[derive(Clone, Debug)]
+pub struct SparseBlock {
+    pub(crate) center: Pt<f64>,
+    pub(crate) radius: f64,
+    /// Centers of the neighboring nodes, in neighbor order
+    pub(crate) ends: SmallVec<[Pt<f64>; DEFAULT_NEIGHBORS]>,
+    /// Graphs that tile the plane (e.g., [`Voronoi`](crate::Voronoi)) draw each node as a polygon instead
+    pub(crate) cell: Option<PolyBlock>,
+}
+
+impl SparseBlock {
+    pub(crate) fn center(&self) -> Pt<f64> {
+        self.center
+    }
+
+    pub(crate) fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    pub(crate) fn cell(&self) -> Option<&PolyBlock> {
+        self.cell.as_ref()
+    }
+
+    /// Point on the edge of a circle with the specified center, in the direction of `to`
+    fn toward(center: Pt<f64>, to: Pt<f64>, radius: f64) -> Pt<f64> {
+        let (dx, dy) = (to.x - center.x, to.y - center.y);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len == 0.0 {
+            return center;
+        }
+        Pt::new(center.x + dx / len * radius, center.y + dy / len * radius)
+    }
+
+    /// Point on the circle facing neighbor `n`
+    pub(crate) fn mid(&self, n: usize) -> Pt<f64> {
+        Self::toward(self.center, self.ends[n], self.radius)
+    }
+
+    /// Line segment between the circle of this node and the circle of neighbor `n`
+    pub(crate) fn segment(&self, n: usize) -> (Pt<f64>, Pt<f64>) {
+        (
+            self.mid(n),
+            Self::toward(self.ends[n], self.center, self.radius),
+        )
+    }
+}
+impl Block for SparseBlock {}
+
+/// A node in a [`SparseGraph`](super::SparseGraph) with a position and any number of neighbors
+#[derive(Clone, Debug)]
+pub struct SparseNode {
+    pub(crate) id: usize,
+    pub(crate) pos: Pt<f64>,
+    pub(crate) n: SmallVec<[Option<usize>; DEFAULT_NEIGHBORS]>, // neighbors
+    pub(crate) links: SmallVec<[Option<usize>; DEFAULT_NEIGHBORS]>,
+}
+
+impl SparseNode {
+    pub(crate) fn new(id: usize, pos: Pt<f64>) -> Self {
+        Self {
+            id,
+            pos,
+            n: SmallVec::new(),
+            links: SmallVec::new(),
+        }
+    }
+
+    pub fn pos(&self) -> Pt<f64> {
+        self.pos
+    }
+}
+
+impl Node for SparseNode {
+    type Block = SparseBlock;
+    const N: usize = DEFAULT_NEIGHBORS;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn all_neighbors(&self) -> &[Option<usize>] {
+        &self.n
+    }
+
+    fn neighbors(&self) -> Neighbors {
+        Neighbors::new(&self.n[..])
+    }
+
+    fn num_neighbors(&self) -> usize {
+        self.n
+            .iter()
+            .fold(0usize, |acc, n| acc + n.is_some() as usize)
+    }
+
+    fn max_neighbors(&self) -> usize {
+        self.n.len()
+    }
+
+    fn neighbor(&self, n: usize) -> Option<usize> {
+        self.n.get(n).copied().flatten()
+    }
+
+    fn neighbor_id(&self, cell: usize) -> Option<usize> {
+        self.n
+            .iter()
+            .position(|n| matches!(n, Some(n) if *n == cell))
+    }
+
+    fn links(&self) -> Neighbors {
+        Neighbors::new(&self.links)
+    }
+
+    fn linked_to(&self, id: usize) -> bool {
+        match self.neighbor_id(id) {
+            Some(n) => self.links[n].is_some(),
+            None => false,
+        }
+    }
+
+    fn linked_side(&self, n: usize) -> bool {
+        matches!(self.links.get(n), Some(Some(_)))
+    }
+
+    fn link(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_none() {
+                    self.links[n] = Some(cell);
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyLinked(self.id, cell))
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_some() {
+                    self.links[n] = None;
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyUnlinked(self.id, cell))
+                }
+            }
+        }
+    }
+}
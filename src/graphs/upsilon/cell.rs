@@ -0,0 +1,224 @@
+use crate::drawing::poly::Polygon;
+use crate::drawing::Pt;
+use crate::graphs::poly::PolyBlock;
+use crate::graphs::{Neighbors, Node};
+use crate::render::opts::blend::Blend;
+use crate::Error;
+use image::{Rgba, RgbaImage};
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+/// An octagon or square cell in an [`Upsilon`](super::Upsilon) graph.
+///
+/// Octagons have their sides ordered: N, NE, E, SE, S, SW, W, NW.
+/// Squares have their sides ordered: N, E, S, W.
+#[derive(Clone, Debug)]
+pub struct UpsilonCell {
+    pub(super) id: usize,
+    pub(super) row: u32,
+    pub(super) col: u32,
+    /// Only the first `max_neighbors()` entries are used
+    pub(super) n: [Option<usize>; 8], // neighbors
+    pub(super) links: [Option<usize>; 8],
+}
+
+impl UpsilonCell {
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    pub fn is_octagon(&self) -> bool {
+        (self.row + self.col) % 2 == 0
+    }
+
+    /// Corners relative to the center of a cell `width` by `height` pixels apart from its orthogonal neighbors
+    fn corners(&self, height: f64, width: f64) -> Vec<Pt<f64>> {
+        // octagons meet their diagonal neighbors halfway between the two centers
+        let apothem = FRAC_1_SQRT_2;
+        let half_side = apothem * (PI / 8.0).tan();
+        let pts: Vec<(f64, f64)> = if self.is_octagon() {
+            let (a, h) = (apothem, half_side);
+            vec![
+                (-h, -a),
+                (h, -a),
+                (a, -h),
+                (a, h),
+                (h, a),
+                (-h, a),
+                (-a, h),
+                (-a, -h),
+            ]
+        } else {
+            let q = 1.0 - apothem;
+            vec![(-q, -q), (q, -q), (q, q), (-q, q)]
+        };
+        pts.iter()
+            .map(|(x, y)| Pt::new(x * width, y * height))
+            .collect()
+    }
+}
+
+impl Node for UpsilonCell {
+    type Block = PolyBlock;
+    const N: usize = 8;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn all_neighbors(&self) -> &[Option<usize>] {
+        &self.n[..self.max_neighbors()]
+    }
+
+    fn neighbors(&self) -> Neighbors {
+        Neighbors::new(self.all_neighbors())
+    }
+
+    fn num_neighbors(&self) -> usize {
+        self.all_neighbors()
+            .iter()
+            .fold(0usize, |acc, n| acc + n.is_some() as usize)
+    }
+
+    fn max_neighbors(&self) -> usize {
+        match self.is_octagon() {
+            true => 8,
+            false => 4,
+        }
+    }
+
+    fn neighbor(&self, n: usize) -> Option<usize> {
+        self.all_neighbors()[n]
+    }
+
+    fn neighbor_id(&self, cell: usize) -> Option<usize> {
+        self.all_neighbors()
+            .iter()
+            .position(|n| matches!(n, Some(n) if *n == cell))
+    }
+
+    fn links(&self) -> Neighbors {
+        Neighbors::new(&self.links[..self.max_neighbors()])
+    }
+
+    fn linked_to(&self, id: usize) -> bool {
+        match self.neighbor_id(id) {
+            Some(n) => self.links[n].is_some(),
+            None => false,
+        }
+    }
+
+    fn linked_side(&self, n: usize) -> bool {
+        self.links[n].is_some()
+    }
+
+    fn link(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_none() {
+                    self.links[n] = Some(cell);
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyLinked(self.id, cell))
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_some() {
+                    self.links[n] = None;
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyUnlinked(self.id, cell))
+                }
+            }
+        }
+    }
+}
+
+impl crate::render::RenderBlock for UpsilonCell {
+    /// `width` and `height` are the distance between the centers of orthogonal neighbors
+    fn block(&self, height: u32, width: u32, padding: u32) -> Self::Block {
+        let (h, w) = (height as f64, width as f64);
+        // octagons extend past the halfway point to their orthogonal neighbors
+        let x = padding as f64 + w * FRAC_1_SQRT_2 + self.col as f64 * w;
+        let y = padding as f64 + h * FRAC_1_SQRT_2 + self.row as f64 * h;
+        PolyBlock::new(Polygon::new(self.corners(h, w)).translate(x, y))
+    }
+
+    fn fill(&self, block: &Self::Block, color: &Rgba<u8>, image: &mut RgbaImage) {
+        block.fill(color, image);
+    }
+
+    fn blend_fill(
+        &self,
+        block: &Self::Block,
+        i: usize,
+        max: usize,
+        blend: &Blend,
+        image: &mut RgbaImage,
+    ) {
+        block.blend_fill(i, max, blend, image);
+    }
+
+    fn edge_unlinked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        block.draw_side(n, color, image);
+    }
+
+    fn edge_linked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        width: u32,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        block.draw_dashed_side(n, width, color, image);
+    }
+
+    fn text_pos(
+        &self,
+        block: &Self::Block,
+        center: bool,
+        padding: freehand::Pt<i32>,
+    ) -> freehand::Pt<u32> {
+        block.text_pos(center, padding)
+    }
+
+    fn arrow(
+        &self,
+        block: &Self::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        block.arrow(from_n, to_n, style, color, image);
+    }
+
+    fn half_arrow(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        block.half_arrow(n, style, color, image);
+    }
+}
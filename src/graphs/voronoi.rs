@@ -0,0 +1,330 @@
+pub(crate) mod diagram;
+
+use super::poly::PolyBlock;
+use super::sparse::node::{SparseBlock, SparseNode};
+use super::sparse::SparseGraph;
+use super::{Graph, Node};
+use crate::drawing::poly::Polygon;
+use crate::drawing::Pt;
+use crate::render::state::graph::{Builder, BuilderGraph};
+use crate::render::RenderGraph;
+use image::{Rgba, RgbaImage};
+use rand::Rng;
+
+/// A graph of irregular cells: the Voronoi diagram of a set of points inside a rectangle.
+///
+/// Each point becomes a node of a [`SparseGraph`], and two nodes are neighbors when their
+/// Voronoi cells share a side.  Side `n` of a cell's polygon is the side shared with neighbor `n`,
+/// and sides along the rectangle have no neighbor.
+///
+/// Sizes and positions are measured in blocks, the same as a [`SparseGraph`].
+#[derive(Clone, Debug)]
+pub struct Voronoi {
+    height: usize,
+    width: usize,
+    graph: SparseGraph,
+    cells: Vec<Polygon>,
+}
+
+impl Voronoi {
+    /// Create a Voronoi graph from Poisson-disk samples, which are random points no closer than `min_dist` to each other.
+    ///
+    /// The same seed always produces the same graph.
+    pub fn random<R>(height: usize, width: usize, min_dist: f64, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        let points = diagram::poisson_disk(height as f64, width as f64, min_dist, rng);
+        Self::from_points(height, width, &points)
+    }
+
+    /// Create a Voronoi graph from a set of points inside a `width` by `height` rectangle
+    pub fn from_points(height: usize, width: usize, points: &[Pt<f64>]) -> Self {
+        let cells = diagram::cells(points, height as f64, width as f64);
+        let nodes = cells
+            .iter()
+            .zip(points)
+            .enumerate()
+            .map(|(id, ((_, sides), pos))| SparseNode {
+                id,
+                pos: *pos,
+                n: sides.iter().copied().collect(),
+                links: sides.iter().map(|_| None).collect(),
+            })
+            .collect();
+        Self {
+            height,
+            width,
+            graph: SparseGraph::from_nodes(nodes),
+            cells: cells
+                .into_iter()
+                .map(|(pts, _)| Polygon::new(pts))
+                .collect(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The underlying graph of positioned nodes
+    pub fn sparse(&self) -> &SparseGraph {
+        &self.graph
+    }
+
+    /// Use default rendering options to render an image
+    pub fn render(&self) -> RgbaImage {
+        self.build_render().finish().render()
+    }
+
+    /// Customize the renderer using a reference to the current graph (preferred)
+    pub fn build_render(&self) -> BuilderGraph<'_, Self> {
+        Builder::graph(self)
+    }
+
+    /// Customize the renderer using the current owned graph
+    pub fn build_render_owned<'g>(self) -> BuilderGraph<'g, Self> {
+        Builder::owned_graph(self)
+    }
+
+    /// Create a distance map from the current graph and a given starting point
+    pub fn dist(&self, start: usize) -> crate::Dist {
+        crate::Dist::simple(self, start)
+    }
+
+    /// Find the shortest path using a given distance map
+    pub fn shortest_path(
+        &self,
+        dist: &crate::Dist,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        dist.shortest_path(self, end)
+    }
+
+    /// Find the shortest path using a weight of 1 for all edges
+    pub fn shortest_path_simple(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        self.dist(start).shortest_path(self, end)
+    }
+
+    fn poly(block: &SparseBlock) -> &PolyBlock {
+        block
+            .cell()
+            .expect("Voronoi blocks are always created with a polygon")
+    }
+}
+
+impl Graph for Voronoi {
+    type Node = SparseNode;
+
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    fn node(&self, id: usize) -> &Self::Node {
+        self.graph.node(id)
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Self::Node {
+        self.graph.node_mut(id)
+    }
+
+    fn nodes(&self) -> Box<dyn Iterator<Item = &Self::Node> + '_> {
+        self.graph.nodes()
+    }
+
+    fn nodes_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Node> + '_> {
+        self.graph.nodes_mut()
+    }
+
+    fn link(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.graph.link(a, b)
+    }
+
+    fn unlink(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.graph.unlink(a, b)
+    }
+}
+
+impl RenderGraph for Voronoi {
+    fn size(&self, block_height: u32, block_width: u32, padding: u32) -> (u32, u32) {
+        (
+            self.width as u32 * block_width + 1 + padding + padding,
+            self.height as u32 * block_height + 1 + padding + padding,
+        )
+    }
+
+    fn blocks(&self, height: u32, width: u32, padding: u32) -> Vec<SparseBlock> {
+        let (h, w, pad) = (height as f64, width as f64, padding as f64);
+        let pixel = |pt: &Pt<f64>| Pt::new(pt.x * w + pad, pt.y * h + pad);
+        self.graph
+            .nodes()
+            .zip(self.cells.iter())
+            .map(|(node, poly)| SparseBlock {
+                center: pixel(&node.pos),
+                radius: 0.0,
+                ends: node
+                    .n
+                    .iter()
+                    .map(|o| match o {
+                        Some(o) => pixel(&self.graph.pos(*o)),
+                        None => pixel(&node.pos),
+                    })
+                    .collect(),
+                cell: Some(PolyBlock::new(Polygon::new(
+                    poly.pts().iter().map(pixel).collect(),
+                ))),
+            })
+            .collect()
+    }
+
+    fn fill(&self, _cell: &SparseNode, block: &SparseBlock, color: &Rgba<u8>, img: &mut RgbaImage) {
+        Self::poly(block).fill(color, img);
+    }
+
+    fn blend_fill(
+        &self,
+        _cell: &SparseNode,
+        block: &SparseBlock,
+        i: usize,
+        max: usize,
+        blend: &crate::render::opts::blend::Blend,
+        image: &mut RgbaImage,
+    ) {
+        Self::poly(block).blend_fill(i, max, blend, image);
+    }
+
+    fn text_pos(
+        &self,
+        _cell: &SparseNode,
+        block: &SparseBlock,
+        center: bool,
+        padding: freehand::Pt<i32>,
+    ) -> freehand::Pt<u32> {
+        Self::poly(block).text_pos(center, padding)
+    }
+
+    fn edge(
+        &self,
+        cell: &SparseNode,
+        block: &SparseBlock,
+        n: usize,
+        dash_width: u32,
+        unlinked_color: &Rgba<u8>,
+        linked_color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if !cell.linked_side(n) {
+            Self::poly(block).draw_side(n, unlinked_color, image);
+        } else if dash_width != 0 {
+            Self::poly(block).draw_dashed_side(n, dash_width, linked_color, image);
+        }
+    }
+
+    fn arrow(
+        &self,
+        _cell: &SparseNode,
+        block: &SparseBlock,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        Self::poly(block).arrow(from_n, to_n, style, color, image);
+    }
+
+    fn half_arrow(
+        &self,
+        _cell: &SparseNode,
+        block: &SparseBlock,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        Self::poly(block).half_arrow(n, style, color, image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn voronoi_neighbors() {
+        crate::logger(log::LevelFilter::Warn);
+        // a 2x2 grid of points splits the rectangle into 4 squares
+        let pts = [
+            Pt::new(1.0, 1.0),
+            Pt::new(3.0, 1.0),
+            Pt::new(1.0, 3.0),
+            Pt::new(3.0, 3.0),
+        ];
+        let grid = Voronoi::from_points(4, 4, &pts);
+        assert_eq!(
+            grid.node(0).all_neighbors(),
+            &[None, Some(1), Some(2), None]
+        );
+        assert_eq!(
+            grid.node(3).all_neighbors(),
+            &[None, None, Some(2), Some(1)]
+        );
+
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let grid = Voronoi::random(12, 12, 1.0, rng);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let again = Voronoi::random(12, 12, 1.0, rng);
+        assert!(grid.len() > 50);
+        assert_eq!(grid.len(), again.len());
+        for cell in grid.nodes() {
+            assert_eq!(cell.pos().x, again.node(cell.id()).pos().x);
+            assert_eq!(cell.max_neighbors(), grid.cells[cell.id()].len());
+            for n in cell.neighbors() {
+                assert!(grid.node(*n).neighbor_id(cell.id()).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn voronoi_path() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Voronoi::random(8, 8, 1.0, rng);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let path = grid.shortest_path_simple(0, grid.len() - 1).unwrap();
+        let renderer = grid.build_render().finish();
+        let path_renderer = crate::render::state::path::Builder::render_state(&renderer)
+            .default_opts()
+            .owned_path(path)
+            .finish();
+        path_renderer.render().save("images/tests/voronoi_path.png")
+    }
+
+    #[test]
+    fn voronoi_bezier_path() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Voronoi::random(6, 6, 1.0, rng);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let path = grid.shortest_path_simple(0, grid.len() - 1).unwrap();
+        let opts = crate::render::opts::PathOpts::build()
+            .arrow_style(crate::render::opts::Arrow::BezierQuad(0.25, 0.75))
+            .build();
+        let renderer = grid.build_render().finish();
+        crate::render::state::path::Builder::render_state(&renderer)
+            .owned_opts(opts)
+            .owned_path(path)
+            .finish()
+            .render();
+    }
+}
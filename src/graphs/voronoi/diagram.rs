@@ -0,0 +1,176 @@
+use crate::drawing::Pt;
+use rand::Rng;
+
+/// Distances smaller than this are treated as zero when clipping
+const EPSILON: f64 = 1e-9;
+
+/// Number of candidates tried around each active point before it is retired
+const ATTEMPTS: usize = 30;
+
+/// Vertices of a cell and the site on the other side of each of its sides
+pub(crate) type Cell = (Vec<Pt<f64>>, Vec<Option<usize>>);
+
+/// Poisson-disk sampling (Bridson's algorithm): random points inside a `width` by
+/// `height` rectangle that are no closer than `min_dist` to each other.
+pub(crate) fn poisson_disk<R: Rng + ?Sized>(
+    height: f64,
+    width: f64,
+    min_dist: f64,
+    rng: &mut R,
+) -> Vec<Pt<f64>> {
+    if height <= 0.0 || width <= 0.0 || min_dist <= 0.0 {
+        return Vec::new();
+    }
+    // each grid square can hold at most one point
+    let size = min_dist / std::f64::consts::SQRT_2;
+    let (cols, rows) = (
+        (width / size).ceil() as usize,
+        (height / size).ceil() as usize,
+    );
+    let mut grid: Vec<Option<usize>> = vec![None; cols * rows];
+    let square = |pt: &Pt<f64>| {
+        (
+            ((pt.x / size) as usize).min(cols - 1),
+            ((pt.y / size) as usize).min(rows - 1),
+        )
+    };
+
+    let first = Pt::new(rng.gen_range(0.0..width), rng.gen_range(0.0..height));
+    let (c, r) = square(&first);
+    grid[r * cols + c] = Some(0);
+    let mut points = vec![first];
+    let mut active = vec![0];
+
+    while !active.is_empty() {
+        let i = rng.gen_range(0..active.len());
+        let center = points[active[i]];
+        let mut found = false;
+        for _ in 0..ATTEMPTS {
+            let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+            let dist = rng.gen_range(min_dist..min_dist * 2.0);
+            let pt = Pt::new(center.x + dist * angle.cos(), center.y + dist * angle.sin());
+            if pt.x < 0.0 || pt.y < 0.0 || pt.x >= width || pt.y >= height {
+                continue;
+            }
+            let (c, r) = square(&pt);
+            let too_close = (r.saturating_sub(2)..(r + 3).min(rows))
+                .flat_map(|r| (c.saturating_sub(2)..(c + 3).min(cols)).map(move |c| (r, c)))
+                .filter_map(|(r, c)| grid[r * cols + c])
+                .any(|p| dist_sq(points[p], pt) < min_dist * min_dist);
+            if !too_close {
+                grid[r * cols + c] = Some(points.len());
+                active.push(points.len());
+                points.push(pt);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            active.swap_remove(i);
+        }
+    }
+    points
+}
+
+/// Voronoi cell of each site, clipped to a `width` by `height` rectangle.
+///
+/// Each cell is a list of vertices in clockwise order (on screen) along with the
+/// site on the other side of each side, where side `n` runs from vertex `n` to `n + 1`.
+/// Sides along the rectangle have no site.
+pub(crate) fn cells(sites: &[Pt<f64>], height: f64, width: f64) -> Vec<Cell> {
+    let mut cells: Vec<Cell> = sites
+        .iter()
+        .enumerate()
+        .map(|(i, site)| {
+            let mut others: Vec<usize> = (0..sites.len()).filter(|j| *j != i).collect();
+            others.sort_by(|a, b| dist_sq(*site, sites[*a]).total_cmp(&dist_sq(*site, sites[*b])));
+
+            let mut pts = vec![
+                Pt::new(0.0, 0.0),
+                Pt::new(width, 0.0),
+                Pt::new(width, height),
+                Pt::new(0.0, height),
+            ];
+            let mut sides = vec![None; 4];
+            for j in others {
+                // sites further away than twice the furthest vertex cannot clip the cell
+                let reach = pts.iter().map(|pt| dist_sq(*site, *pt)).fold(0.0, f64::max);
+                if dist_sq(*site, sites[j]) > 4.0 * reach {
+                    break;
+                }
+                (pts, sides) = clip(&pts, &sides, *site, sites[j], j);
+            }
+            (pts, sides)
+        })
+        .collect();
+
+    // drop sides only one of the two cells found, which can happen with nearly degenerate vertices
+    let found: Vec<Vec<Option<usize>>> = cells.iter().map(|c| c.1.clone()).collect();
+    for (i, (_, sides)) in cells.iter_mut().enumerate() {
+        for side in sides.iter_mut() {
+            if matches!(side, Some(j) if !found[*j].contains(&Some(i))) {
+                *side = None;
+            }
+        }
+    }
+    cells
+}
+
+/// Clip a convex cell to the half-plane closer to `site` than to `other`
+fn clip(
+    pts: &[Pt<f64>],
+    sides: &[Option<usize>],
+    site: Pt<f64>,
+    other: Pt<f64>,
+    other_id: usize,
+) -> (Vec<Pt<f64>>, Vec<Option<usize>>) {
+    let mid = Pt::new((site.x + other.x) / 2.0, (site.y + other.y) / 2.0);
+    let dir = Pt::new(other.x - site.x, other.y - site.y);
+    let dist = |pt: Pt<f64>| (pt.x - mid.x) * dir.x + (pt.y - mid.y) * dir.y;
+    let intersect = |a: Pt<f64>, b: Pt<f64>| {
+        let (da, db) = (dist(a), dist(b));
+        let t = da / (da - db);
+        Pt::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+    };
+
+    let mut out_pts = Vec::with_capacity(pts.len() + 1);
+    let mut out_sides = Vec::with_capacity(pts.len() + 1);
+    for k in 0..pts.len() {
+        let (a, b) = (pts[k], pts[(k + 1) % pts.len()]);
+        let (a_in, b_in) = (dist(a) <= EPSILON, dist(b) <= EPSILON);
+        match (a_in, b_in) {
+            (true, true) => {
+                out_pts.push(a);
+                out_sides.push(sides[k]);
+            }
+            (true, false) => {
+                out_pts.push(a);
+                out_sides.push(sides[k]);
+                out_pts.push(intersect(a, b));
+                out_sides.push(Some(other_id));
+            }
+            (false, true) => {
+                out_pts.push(intersect(a, b));
+                out_sides.push(sides[k]);
+            }
+            (false, false) => {}
+        }
+    }
+
+    // remove sides with no length
+    let mut k = 0;
+    while out_pts.len() > 3 && k < out_pts.len() {
+        let next = (k + 1) % out_pts.len();
+        if dist_sq(out_pts[k], out_pts[next]) < EPSILON {
+            out_pts.remove(k);
+            out_sides.remove(k);
+        } else {
+            k += 1;
+        }
+    }
+    (out_pts, out_sides)
+}
+
+fn dist_sq(a: Pt<f64>, b: Pt<f64>) -> f64 {
+    (a.x - b.x) * (a.x - b.x) + (a.y - b.y) * (a.y - b.y)
+}
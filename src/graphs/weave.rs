@@ -0,0 +1,442 @@
+pub(crate) mod cell;
+
+use super::{Graph, Node};
+use crate::render::state::graph::{Builder, BuilderGraph};
+use crate::render::{RenderBlock, RenderGraph};
+use cell::WeaveCell;
+use image::{Rgba, RgbaImage};
+use smallvec::SmallVec;
+
+/// A rectangular grid where passages may tunnel under a perpendicular corridor.
+///
+/// A tunnel is a single link between two cells in the same row or column with one
+/// cell between them.  The cell in the middle must already have a passage running
+/// straight across the tunnel, and it records the crossing so it can be rendered.
+///
+/// Generators that pick from [`Graph::unlinked_neighbors`] and [`Graph::linked_neighbors`]
+/// (e.g., [`huntkill`](crate::algo::hk::huntkill)) create tunnels automatically.
+#[derive(Clone, Debug)]
+pub struct Weave {
+    len: usize,
+    height: usize,
+    width: usize,
+    cells: Vec<WeaveCell>,
+}
+
+impl Weave {
+    /// Create a new weave grid without any links between cells
+    pub fn new(height: usize, width: usize) -> Self {
+        let len = height * width;
+        Self {
+            len,
+            height,
+            width,
+            cells: (0..len)
+                .map(|id| Self::new_cell(id, height, width))
+                .collect(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Whether a tunnel can be dug from `id` through side `n` (4-7) under the adjacent cell.
+    ///
+    /// The cell being passed under must be linked on both sides perpendicular to the
+    /// tunnel, and have no passage on either side along it, including its own tunnels.
+    /// Neither end of the tunnel may already be crossed under along the same direction.
+    pub fn can_tunnel(&self, id: usize, n: usize) -> bool {
+        if !(4..8).contains(&n) {
+            return false;
+        }
+        let (Some(mid), Some(dest)) = (self.cells[id].n[n - 4], self.cells[id].n[n]) else {
+            return false;
+        };
+        let (mid, along, across) = (&self.cells[mid], n % 2, (n + 1) % 2);
+        mid.under.is_none()
+            && !mid.open(along)
+            && !mid.open(along + 2)
+            && mid.links[across].is_some()
+            && mid.links[across + 2].is_some()
+            && !self.cells[id].crossed_under(along)
+            && !self.cells[dest].crossed_under(along)
+    }
+
+    /// Use default rendering options to render an image
+    pub fn render(&self) -> RgbaImage {
+        self.build_render().finish().render()
+    }
+
+    /// Customize the renderer using a reference to the current graph (preferred)
+    pub fn build_render(&self) -> BuilderGraph<'_, Self> {
+        Builder::graph(self)
+    }
+
+    /// Customize the renderer using the current owned graph
+    pub fn build_render_owned<'g>(self) -> BuilderGraph<'g, Self> {
+        Builder::owned_graph(self)
+    }
+
+    /// Create a distance map from the current graph and a given starting point
+    pub fn dist(&self, start: usize) -> crate::Dist {
+        crate::Dist::simple(self, start)
+    }
+
+    /// Find the shortest path using a given distance map
+    pub fn shortest_path(
+        &self,
+        dist: &crate::Dist,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        dist.shortest_path(self, end)
+    }
+
+    /// Find the shortest path using a weight of 1 for all edges
+    pub fn shortest_path_simple(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<crate::Path, crate::Error> {
+        self.dist(start).shortest_path(self, end)
+    }
+
+    fn check_id(&self, cell: usize) -> Result<(), crate::Error> {
+        match cell >= self.len {
+            true => Err(crate::Error::InvalidCell(cell, self.len)),
+            false => Ok(()),
+        }
+    }
+
+    /// Whether side `n` of a cell may be used: cells being passed under cannot link along the tunnel
+    fn side_available(&self, id: usize, n: usize) -> bool {
+        match n {
+            0..=3 => {
+                !self.cells[id].crossed_under(n)
+                    && !matches!(self.cells[id].n[n], Some(o) if self.cells[o].crossed_under(n))
+            }
+            _ => self.can_tunnel(id, n),
+        }
+    }
+
+    fn cell_id(row: isize, col: isize, height: usize, width: usize) -> Option<usize> {
+        if row < 0 || col < 0 || row >= height as isize || col >= width as isize {
+            None
+        } else {
+            Some(row as usize * width + col as usize)
+        }
+    }
+
+    fn new_cell(id: usize, height: usize, width: usize) -> WeaveCell {
+        let (row, col) = ((id / width) as isize, (id % width) as isize);
+        let at = |r: isize, c: isize| Self::cell_id(r, c, height, width);
+        WeaveCell {
+            id,
+            row: row as u32,
+            col: col as u32,
+            n: [
+                at(row - 1, col),
+                at(row, col + 1),
+                at(row + 1, col),
+                at(row, col - 1),
+                at(row - 2, col),
+                at(row, col + 2),
+                at(row + 2, col),
+                at(row, col - 2),
+            ],
+            links: [None; 8],
+            under: None,
+        }
+    }
+}
+
+impl Graph for Weave {
+    type Node = WeaveCell;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn node(&self, id: usize) -> &Self::Node {
+        &self.cells[id]
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Self::Node {
+        &mut self.cells[id]
+    }
+
+    fn nodes(&self) -> Box<dyn Iterator<Item = &Self::Node> + '_> {
+        Box::new(self.cells.iter())
+    }
+
+    fn nodes_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Node> + '_> {
+        Box::new(self.cells.iter_mut())
+    }
+
+    /// Links two cells.  Linking cells two steps apart digs a tunnel under the cell between them.
+    fn link(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a).and_then(|_| self.check_id(b))?;
+        let n = self.cells[a]
+            .neighbor_id(b)
+            .ok_or(crate::Error::InvalidNeighbor(a, b))?;
+        if !self.side_available(a, n) {
+            return Err(crate::Error::InvalidNeighbor(a, b));
+        }
+        self.cells[a].link(b).and_then(|_| self.cells[b].link(a))?;
+        if n >= 4 {
+            let mid = self.cells[a].n[n - 4].unwrap();
+            self.cells[mid].under = Some(n % 2);
+        }
+        Ok(())
+    }
+
+    fn unlink(&mut self, a: usize, b: usize) -> Result<(), crate::Error> {
+        self.check_id(a)
+            .and_then(|_| self.check_id(b))
+            .and_then(|_| {
+                self.cells[a]
+                    .unlink(b)
+                    .and_then(|_| self.cells[b].unlink(a))
+            })?;
+        if let Some(n @ 4..=7) = self.cells[a].neighbor_id(b) {
+            let mid = self.cells[a].n[n - 4].unwrap();
+            self.cells[mid].under = None;
+        }
+        Ok(())
+    }
+
+    /// Linked cells that can be reached either directly or through a tunnel
+    fn linked_neighbors(&self, node: usize) -> SmallVec<[usize; crate::DEFAULT_NEIGHBORS]> {
+        self.cells[node]
+            .n
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| n.map(|n| (i, n)))
+            .filter(|(i, n)| !self.cells[*n].is_empty() && self.side_available(node, *i))
+            .map(|(_, n)| n)
+            .collect()
+    }
+
+    /// Empty cells that can be reached either directly or through a tunnel
+    fn unlinked_neighbors(&self, node: usize) -> SmallVec<[usize; crate::DEFAULT_NEIGHBORS]> {
+        self.cells[node]
+            .n
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| n.map(|n| (i, n)))
+            .filter(|(i, n)| self.cells[*n].is_empty() && self.side_available(node, *i))
+            .map(|(_, n)| n)
+            .collect()
+    }
+}
+
+impl RenderGraph for Weave {
+    fn size(&self, block_height: u32, block_width: u32, padding: u32) -> (u32, u32) {
+        (
+            (self.width) as u32 * (block_width + 1) + padding + padding,
+            (self.height) as u32 * (block_height + 1) + padding + padding,
+        )
+    }
+
+    fn blocks(&self, height: u32, width: u32, padding: u32) -> Vec<<Self::Node as Node>::Block> {
+        self.cells
+            .iter()
+            .map(|c| c.block(height, width, padding))
+            .collect()
+    }
+
+    fn fill(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        color: &Rgba<u8>,
+        img: &mut RgbaImage,
+    ) {
+        cell.fill(block, color, img);
+    }
+
+    fn blend_fill(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        i: usize,
+        max: usize,
+        blend: &crate::render::opts::blend::Blend,
+        image: &mut RgbaImage,
+    ) {
+        cell.blend_fill(block, i, max, blend, image);
+    }
+
+    fn text_pos(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        center: bool,
+        padding: freehand::Pt<i32>,
+    ) -> freehand::Pt<u32> {
+        cell.text_pos(block, center, padding)
+    }
+
+    /// Draws the walls on both sides of the border, since cells are inset from their borders.
+    ///
+    /// Tunnel sides (4-7) are drawn as part of the adjacent sides they pass through.
+    fn edge(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        n: usize,
+        dash_width: u32,
+        unlinked_color: &Rgba<u8>,
+        linked_color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if n >= 4 {
+            return;
+        }
+        let other = cell.neighbor(n).map(|id| &self.cells[id]);
+        if !cell.open(n) {
+            cell.edge_unlinked(block, n, unlinked_color, image);
+            if other.is_some() {
+                block
+                    .shift(n)
+                    .draw_inner_side((n + 2) % 4, *unlinked_color, image);
+            }
+            return;
+        }
+
+        block.draw_passage(n, *unlinked_color, image);
+        // the walls of a corridor running over a tunnel
+        if cell.crossed_under(n) {
+            block.draw_inner_side(n, *unlinked_color, image);
+        }
+        if matches!(other, Some(o) if o.crossed_under(n)) {
+            block
+                .shift(n)
+                .draw_inner_side((n + 2) % 4, *unlinked_color, image);
+        }
+        if cell.linked_side(n) && dash_width != 0 {
+            cell.edge_linked(block, n, dash_width, linked_color, image);
+        }
+    }
+
+    fn arrow(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        cell.arrow(block, from_n, to_n, style, color, image);
+    }
+
+    fn half_arrow(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        cell.half_arrow(block, n, style, color, image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xoshiro::SplitMix64;
+
+    #[test]
+    fn weave_tunnel() {
+        crate::logger(log::LevelFilter::Warn);
+        // 0 1 2
+        // 3 4 5
+        // 6 7 8
+        let mut grid = Weave::new(3, 3);
+        assert!(grid.link(3, 5).is_err());
+        grid.link(1, 4).unwrap();
+        grid.link(4, 7).unwrap();
+        assert!(grid.can_tunnel(3, 5));
+        assert_eq!(grid.unlinked_neighbors(3).as_slice(), &[0, 6, 5]);
+        grid.link(3, 5).unwrap();
+        assert!(grid.node(4).under());
+        assert!(grid.node(4).crossed_under(1));
+        assert!(grid.link(4, 5).is_err());
+
+        let dist = grid.dist(3);
+        assert_eq!(dist.dist(5), Some(1));
+        assert_eq!(dist.dist(4), None);
+        let path = grid.shortest_path_simple(3, 5).unwrap();
+        assert_eq!(path.path, vec![3, 5]);
+
+        grid.unlink(3, 5).unwrap();
+        assert!(!grid.node(4).under());
+    }
+
+    #[test]
+    fn weave_overlapping_tunnels() {
+        crate::logger(log::LevelFilter::Warn);
+        // 0 1 2 3
+        // 4 5 6 7
+        // 8 9 a b
+        let mut grid = Weave::new(3, 4);
+        for (a, b) in [(1, 5), (5, 9), (2, 6), (6, 10)] {
+            grid.link(a, b).unwrap();
+        }
+        // 5 tunnels under 6 to 7
+        grid.link(5, 7).unwrap();
+        // 4 can't tunnel under 5, which has its own tunnel, or come up in 6, which is crossed under
+        assert!(!grid.can_tunnel(4, 5));
+        assert!(grid.link(4, 6).is_err());
+        assert!(!grid.unlinked_neighbors(4).contains(&6));
+        assert!(!grid.linked_neighbors(4).contains(&6));
+
+        grid.unlink(5, 7).unwrap();
+        assert!(grid.can_tunnel(4, 5));
+    }
+
+    #[test]
+    fn weave_path() -> Result<(), image::ImageError> {
+        crate::logger(crate::LOG_LEVEL);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Weave::new(8, 8);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        assert!(grid.nodes().any(|c| c.under()));
+        assert!(grid.nodes().all(|c| !c.is_empty()));
+
+        let path = grid.shortest_path_simple(0, grid.len() - 1).unwrap();
+        let renderer = grid.build_render().finish();
+        let path_renderer = crate::render::state::path::Builder::render_state(&renderer)
+            .default_opts()
+            .owned_path(path)
+            .finish();
+        path_renderer.render().save("images/tests/weave_path.png")
+    }
+
+    #[test]
+    fn weave_bezier_path() {
+        crate::logger(log::LevelFilter::Warn);
+        let rng = &mut SplitMix64::seed_from_u64(13131313131313131313);
+        let mut grid = Weave::new(6, 6);
+        crate::algo::hk::huntkill(&mut grid, rng).unwrap();
+        let path = grid.shortest_path_simple(0, grid.len() - 1).unwrap();
+        let opts = crate::render::opts::PathOpts::build()
+            .arrow_style(crate::render::opts::Arrow::BezierQuad(0.25, 0.75))
+            .build();
+        let renderer = grid.build_render().finish();
+        crate::render::state::path::Builder::render_state(&renderer)
+            .owned_opts(opts)
+            .owned_path(path)
+            .finish()
+            .render();
+    }
+}
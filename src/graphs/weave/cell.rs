@@ -0,0 +1,347 @@
+use crate::drawing::poly::{draw_line, Polygon};
+use crate::drawing::Pt;
+use crate::graphs::{Block, Neighbors, Node};
+use crate::render::opts::blend::Blend;
+use crate::Error;
+use freehand::Pt as FPt;
+use image::{Rgba, RgbaImage};
+
+/// Fraction of the block size between the border of a block and the walls of its cell
+const INSET: f64 = 0.2;
+
+/// A rectangular block where the walls of the cell are drawn inset from the border,
+/// leaving room for passages to be drawn underneath neighboring cells.
+#[derive(Clone, Debug)]
+pub struct WeaveBlock {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl WeaveBlock {
+    fn inset(&self) -> f64 {
+        (self.width.min(self.height) * INSET).round()
+    }
+
+    /// Top left and bottom right corners of the inset walls
+    fn inner(&self) -> (Pt<f64>, Pt<f64>) {
+        let i = self.inset();
+        (
+            Pt::new(self.x + i, self.y + i),
+            Pt::new(self.x + self.width - i, self.y + self.height - i),
+        )
+    }
+
+    pub(crate) fn center(&self) -> Pt<f64> {
+        Pt::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// The block of the adjacent cell on side `n` (N, E, S, W)
+    pub(crate) fn shift(&self, n: usize) -> Self {
+        // the +1 accounts for borders, assuming border size of 1
+        let (dx, dy) = match n {
+            0 => (0.0, -(self.height + 1.0)),
+            1 => (self.width + 1.0, 0.0),
+            2 => (0.0, self.height + 1.0),
+            3 => (-(self.width + 1.0), 0.0),
+            _ => panic!("Invalid edge {}", n),
+        };
+        Self {
+            x: self.x + dx,
+            y: self.y + dy,
+            ..*self
+        }
+    }
+
+    /// Midpoint of the border on side `n`.
+    ///
+    /// Sides 4-7 lead under the adjacent cell, so their midpoint is the center of that cell.
+    pub(crate) fn mid(&self, n: usize) -> Pt<f64> {
+        let c = self.center();
+        match n {
+            0 => Pt::new(c.x, self.y),
+            1 => Pt::new(self.x + self.width, c.y),
+            2 => Pt::new(c.x, self.y + self.height),
+            3 => Pt::new(self.x, c.y),
+            4..=7 => self.shift(n - 4).center(),
+            _ => panic!("Invalid edge {}", n),
+        }
+    }
+
+    /// The inset wall on side `n`
+    pub(crate) fn inner_side(&self, n: usize) -> (Pt<f64>, Pt<f64>) {
+        let (a, b) = self.inner();
+        match n {
+            0 => (a, Pt::new(b.x, a.y)),
+            1 => (Pt::new(b.x, a.y), b),
+            2 => (Pt::new(a.x, b.y), b),
+            3 => (a, Pt::new(a.x, b.y)),
+            _ => panic!("Invalid edge {}", n),
+        }
+    }
+
+    /// The part of the border on side `n` that a passage runs through
+    pub(crate) fn border_side(&self, n: usize) -> (Pt<f64>, Pt<f64>) {
+        let (a, b) = self.inner();
+        let (right, bottom) = (self.x + self.width, self.y + self.height);
+        match n {
+            0 => (Pt::new(a.x, self.y), Pt::new(b.x, self.y)),
+            1 => (Pt::new(right, a.y), Pt::new(right, b.y)),
+            2 => (Pt::new(a.x, bottom), Pt::new(b.x, bottom)),
+            3 => (Pt::new(self.x, a.y), Pt::new(self.x, b.y)),
+            _ => panic!("Invalid edge {}", n),
+        }
+    }
+
+    /// Area of a passage leaving the cell on side `n`, from the inset wall to the border
+    fn passage(&self, n: usize) -> Polygon {
+        let (a, b) = self.inner_side(n);
+        let (c, d) = self.border_side(n);
+        Polygon::new(vec![a, b, d, c])
+    }
+
+    pub(crate) fn draw_inner_side(&self, n: usize, color: Rgba<u8>, image: &mut RgbaImage) {
+        let (a, b) = self.inner_side(n);
+        draw_line(image, a, b, color);
+    }
+
+    /// Draw the walls of a passage running from this cell to the adjacent cell on side `n`
+    pub(crate) fn draw_passage(&self, n: usize, color: Rgba<u8>, image: &mut RgbaImage) {
+        let (a, b) = self.inner_side(n);
+        let (c, d) = self.shift(n).inner_side((n + 2) % 4);
+        draw_line(image, a, c, color);
+        draw_line(image, b, d, color);
+    }
+}
+impl Block for WeaveBlock {}
+
+/// A cell in a [`Weave`](super::Weave) graph.
+///
+/// Sides 0-3 are the adjacent cells (N, E, S, W) and sides 4-7 are the cells two
+/// steps away in the same directions, reached by tunneling under the adjacent cell.
+#[derive(Clone, Debug)]
+pub struct WeaveCell {
+    pub(super) id: usize,
+    pub(super) row: u32,
+    pub(super) col: u32,
+    pub(super) n: [Option<usize>; 8], // neighbors
+    pub(super) links: [Option<usize>; 8],
+    /// Axis of a passage tunneling under this cell: 0 for north-south or 1 for east-west
+    pub(super) under: Option<usize>,
+}
+
+impl WeaveCell {
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    /// Whether a passage tunnels under this cell
+    pub fn under(&self) -> bool {
+        self.under.is_some()
+    }
+
+    /// Whether a passage tunnels under this cell through side `n` (N, E, S, W)
+    pub fn crossed_under(&self, n: usize) -> bool {
+        self.under == Some(n % 2)
+    }
+
+    /// Whether a passage leaves the cell through side `n` (N, E, S, W), including passages that tunnel under a neighbor
+    pub fn open(&self, n: usize) -> bool {
+        self.links[n].is_some() || self.links[n + 4].is_some() || self.crossed_under(n)
+    }
+}
+
+impl Node for WeaveCell {
+    type Block = WeaveBlock;
+    const N: usize = 8;
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn all_neighbors(&self) -> &[Option<usize>] {
+        &self.n
+    }
+
+    fn neighbors(&self) -> crate::graphs::Neighbors {
+        crate::graphs::Neighbors::new(&self.n[..])
+    }
+
+    fn num_neighbors(&self) -> usize {
+        self.n
+            .iter()
+            .fold(0usize, |acc, n| acc + n.is_some() as usize)
+    }
+
+    fn neighbor(&self, n: usize) -> Option<usize> {
+        self.n[n]
+    }
+
+    fn neighbor_id(&self, cell: usize) -> Option<usize> {
+        self.n
+            .iter()
+            .position(|n| matches!(n, Some(n) if *n == cell))
+    }
+
+    fn links(&self) -> Neighbors {
+        Neighbors::new(&self.links)
+    }
+
+    fn linked_to(&self, id: usize) -> bool {
+        match self.neighbor_id(id) {
+            Some(n) => self.links[n].is_some(),
+            None => false,
+        }
+    }
+
+    fn linked_side(&self, n: usize) -> bool {
+        self.links[n].is_some()
+    }
+
+    fn link(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_none() {
+                    self.links[n] = Some(cell);
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyLinked(self.id, cell))
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, cell: usize) -> Result<(), Error> {
+        match self.neighbor_id(cell) {
+            None => Err(Error::InvalidNeighbor(self.id, cell)),
+            Some(n) => {
+                if self.links[n].is_some() {
+                    self.links[n] = None;
+                    Ok(())
+                } else {
+                    Err(Error::AlreadyUnlinked(self.id, cell))
+                }
+            }
+        }
+    }
+}
+
+impl crate::render::RenderBlock for WeaveCell {
+    fn block(&self, height: u32, width: u32, padding: u32) -> Self::Block {
+        // the +1 accounts for borders, assuming border size of 1
+        WeaveBlock {
+            x: (width * self.col + self.col + padding) as f64,
+            y: (height * self.row + self.row + padding) as f64,
+            width: width as f64,
+            height: height as f64,
+        }
+    }
+
+    /// Fills the inset area along with any passages leaving the cell
+    fn fill(&self, block: &Self::Block, color: &Rgba<u8>, image: &mut RgbaImage) {
+        let (a, b) = block.inner();
+        Polygon::new(vec![a, Pt::new(b.x, a.y), b, Pt::new(a.x, b.y)]).fill(image, *color);
+        for n in (0..4).filter(|n| self.links[*n].is_some() || self.links[n + 4].is_some()) {
+            block.passage(n).fill(image, *color);
+        }
+    }
+
+    fn blend_fill(
+        &self,
+        block: &Self::Block,
+        i: usize,
+        max: usize,
+        blend: &Blend,
+        image: &mut RgbaImage,
+    ) {
+        self.fill(block, &blend.color(i, max), image);
+    }
+
+    /// Draw the inset wall.  Sides leading under a neighbor have no wall of their own.
+    fn edge_unlinked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if n < 4 {
+            block.draw_inner_side(n, *color, image);
+        }
+    }
+
+    /// Draw a dashed line across the passage where it crosses the border
+    fn edge_linked(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        width: u32,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        if n < 4 {
+            let (a, b) = block.border_side(n);
+            crate::drawing::draw_dashed(
+                &crate::drawing::line_points(a, b),
+                width,
+                color[3] as f32 / 255.0,
+                image,
+                *color,
+            );
+        }
+    }
+
+    fn text_pos(&self, block: &Self::Block, center: bool, padding: FPt<i32>) -> FPt<u32> {
+        let pt = if center {
+            block.center().i32()
+        } else {
+            block.inner().0.i32()
+        };
+        FPt::new(pt.x + padding.x(), pt.y + padding.y()).u32()
+    }
+
+    fn arrow(
+        &self,
+        block: &Self::Block,
+        from_n: usize,
+        to_n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            // bezier curves fall back to straight lines
+            Arrow::Straight | Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                self.half_arrow(block, from_n, style, color, image);
+                self.half_arrow(block, to_n, style, color, image);
+            }
+        }
+    }
+
+    /// Arrows through a tunnel extend to the center of the cell being passed under
+    fn half_arrow(
+        &self,
+        block: &Self::Block,
+        n: usize,
+        style: &crate::render::opts::Arrow,
+        color: Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+        use crate::render::opts::Arrow;
+
+        match style {
+            // bezier curves fall back to straight lines
+            Arrow::Straight | Arrow::StraightCenter | Arrow::BezierQuad(_, _) => {
+                draw_line(image, block.mid(n), block.center(), color)
+            }
+        }
+    }
+}
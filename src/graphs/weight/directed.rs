@@ -0,0 +1,97 @@
+use super::Weights;
+use crate::graphs::{Graph, Node};
+use crate::Error;
+use crate::DEFAULT_NEIGHBORS;
+use smallvec::SmallVec;
+
+/// Edge weights where each direction has its own cost, e.g. uphill and downhill
+#[derive(Clone, Debug)]
+pub struct Directed {
+    weights: Vec<SmallVec<[Option<usize>; DEFAULT_NEIGHBORS]>>,
+}
+impl Directed {
+    /// Weight of moving from `id` across `side`, from `f(graph, id, side)`, called once for each direction of every edge
+    pub fn new_with<G: Graph, F: Fn(&G, usize, usize) -> usize>(graph: &G, f: F) -> Self {
+        let weights = (0..graph.len())
+            .map(|id| {
+                let node = graph.node(id);
+                (0..node.max_neighbors())
+                    .map(|side| node.neighbor(side).map(|_| f(graph, id, side)))
+                    .collect()
+            })
+            .collect();
+        Self { weights }
+    }
+
+    pub fn from_slice<const N: usize, G: Graph>(
+        graph: &G,
+        slice: &[[usize; N]],
+    ) -> Result<Self, Error> {
+        if slice.len() != graph.len() {
+            Err(Error::MismatchedListSize(slice.len(), graph.len()))
+        } else {
+            Ok(Self::new_with(graph, |_, id, n| slice[id][n]))
+        }
+    }
+
+    pub fn weight(&self, id: usize, n: usize) -> Result<usize, Error> {
+        if id >= self.weights.len() {
+            Err(Error::InvalidId(id, self.weights.len()))
+        } else if n >= self.weights[id].len() {
+            Err(Error::InvalidNeighbor(n, self.weights[id].len()))
+        } else {
+            self.weights[id][n].ok_or(Error::InvalidEdge(id, n))
+        }
+    }
+
+    pub fn set_weight(&mut self, id: usize, n: usize, weight: usize) -> Result<(), Error> {
+        self.weight(id, n)?;
+        self.weights[id][n] = Some(weight);
+        Ok(())
+    }
+}
+
+impl Weights for Directed {
+    fn weight(&self, id: usize, n: usize) -> Result<usize, Error> {
+        Directed::weight(self, id, n)
+    }
+
+    fn min_weight(&self) -> usize {
+        self.weights
+            .iter()
+            .flatten()
+            .flatten()
+            .copied()
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Dist, Orth, RectCell};
+
+    #[test]
+    fn directed_weights() {
+        crate::logger(log::LevelFilter::Warn);
+        let grid: Orth<RectCell> = Orth::new_linked(1, 3);
+        // going east is downhill
+        let mut weights = Directed::new_with(&grid, |_, _, side| if side == 1 { 1 } else { 5 });
+        assert_eq!(weights.weight(0, 1).unwrap(), 1);
+        assert_eq!(weights.weight(1, 3).unwrap(), 5);
+        assert!(weights.weight(0, 0).is_err());
+        assert!(weights.set_weight(0, 3, 1).is_err());
+        assert_eq!(weights.min_weight(), 1);
+
+        let east = Dist::weighted(&grid, 0, &weights).unwrap();
+        let west = Dist::weighted(&grid, 2, &weights).unwrap();
+        assert_eq!(east.dist(2), Some(2));
+        assert_eq!(west.dist(0), Some(10));
+
+        weights.set_weight(2, 3, 1).unwrap();
+        let west = Dist::weighted(&grid, 2, &weights).unwrap();
+        assert_eq!(west.dist(0), Some(6));
+        assert_eq!(west.shortest_path(&grid, 0).unwrap().path, vec![2, 1, 0]);
+    }
+}
@@ -14,8 +14,25 @@ pub use crate::algo::dist::Dist;
 pub use crate::algo::path::Path;
 pub use crate::drawing::Pt;
 pub use crate::error::Error;
+pub use crate::graphs::delta::cell::DeltaCell;
+pub use crate::graphs::delta::Delta;
+pub use crate::graphs::hex::cell::{HexCell, HexOrientation};
+pub use crate::graphs::hex::Hex;
+pub use crate::graphs::levels::cell::LevelCell;
+pub use crate::graphs::levels::Levels;
+pub use crate::graphs::orth::mask::Mask;
 pub use crate::graphs::orth::nodes::rect::RectCell;
-pub use crate::graphs::orth::Orth;
+pub use crate::graphs::orth::{Orth, Wrap};
+pub use crate::graphs::polar::cell::PolarCell;
+pub use crate::graphs::polar::Polar;
+pub use crate::graphs::sparse::node::SparseNode;
+pub use crate::graphs::sparse::SparseGraph;
+pub use crate::graphs::upsilon::cell::UpsilonCell;
+pub use crate::graphs::upsilon::Upsilon;
+pub use crate::graphs::voronoi::Voronoi;
+pub use crate::graphs::weave::cell::WeaveCell;
+pub use crate::graphs::weave::Weave;
+pub use crate::graphs::weight;
 pub use crate::graphs::Graph;
 pub use crate::render::opts;
 
@@ -131,6 +131,38 @@ pub trait RenderGraph: Graph {
         color: Rgba<u8>,
         image: &mut RgbaImage,
     );
+
+    /// Draw the outline of a node, once for every node before its edges are drawn, in the inner
+    /// edge color.
+    ///
+    /// Does nothing by default: most graphs are fully outlined by their edges.  Graphs that draw
+    /// something around a node that is not one of its edges (e.g., the circle around a
+    /// [`SparseGraph`](crate::SparseGraph) node) draw it here.
+    fn outline(
+        &self,
+        cell: &Self::Node,
+        block: &<Self::Node as Node>::Block,
+        color: &Rgba<u8>,
+        image: &mut RgbaImage,
+    ) {
+    }
+
+    /// Whether an edge joins two sides of a graph that wraps around (e.g., a cylinder).
+    ///
+    /// Seam edges are drawn on both of their sides with a separate color so they
+    /// are not mistaken for outer walls.
+    fn seam(&self, cell: &Self::Node, n: usize) -> bool {
+        false
+    }
+
+    /// Whether an edge joins two cells that are drawn apart from each other (e.g., stacked
+    /// levels), so it has to be drawn in both cells.  Seams are always split.
+    ///
+    /// By default only [`seam`](RenderGraph::seam) edges are split; every other edge is drawn once,
+    /// from the node that lists it first.
+    fn split(&self, cell: &Self::Node, n: usize) -> bool {
+        self.seam(cell, n)
+    }
 }
 
 pub(crate) trait RenderState<'b, 'c, 'e, 'g, 'o> {
@@ -85,6 +85,8 @@ pub struct Colors {
     /// Dashed edges will be blended using the color's alpha channel
     dashed_edges: Rgba<u8>,
     outer_edges: Rgba<u8>,
+    /// Unlinked edges where a graph wraps around to the other side
+    seam_edges: Rgba<u8>,
     text: Rgba<u8>,
 }
 
@@ -117,6 +119,10 @@ impl Colors {
         &self.outer_edges
     }
 
+    pub fn seam_edges(&self) -> &Rgba<u8> {
+        &self.seam_edges
+    }
+
     pub fn text(&self) -> &Rgba<u8> {
         &self.text
     }
@@ -131,6 +137,7 @@ impl Default for Colors {
             edges: Rgba([80, 80, 80, 255]),
             dashed_edges: Rgba([210, 210, 210, 128]),
             outer_edges: Rgba([0, 0, 0, 255]),
+            seam_edges: Rgba([66, 135, 245, 255]),
             text: Rgba([0, 0, 0, 255]),
         }
     }
@@ -298,6 +305,7 @@ pub struct ColorsBuilder {
     edges: Option<Rgba<u8>>,
     dashed_edges: Option<Rgba<u8>>,
     outer_edges: Option<Rgba<u8>>,
+    seam_edges: Option<Rgba<u8>>,
     text: Option<Rgba<u8>>,
 }
 
@@ -315,6 +323,7 @@ impl ColorsBuilder {
             edges: self.edges.unwrap_or(defaults.edges),
             dashed_edges: self.dashed_edges.unwrap_or(defaults.dashed_edges),
             outer_edges: self.outer_edges.unwrap_or(defaults.outer_edges),
+            seam_edges: self.seam_edges.unwrap_or(defaults.seam_edges),
             text: self.text.unwrap_or(defaults.text),
         }
     }
@@ -361,6 +370,13 @@ impl ColorsBuilder {
         }
     }
 
+    pub fn seam_edges(self, color: Rgba<u8>) -> Self {
+        Self {
+            seam_edges: Some(color),
+            ..self
+        }
+    }
+
     pub fn text(self, color: Rgba<u8>) -> Self {
         Self {
             text: Some(color),
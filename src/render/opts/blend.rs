@@ -50,6 +50,18 @@ impl Blend {
 
         Ok(Self::HslIntensity(hsl::HSL { h, s, l: 1.0 }, min_l, max_l))
     }
+
+    /// Find the color for step `i` out of `max` steps.
+    pub(crate) fn color(&self, i: usize, max: usize) -> Rgba<u8> {
+        match self {
+            Self::None(color) => *color,
+            Self::RgbIntensity(color) => rgb_intensity(color, calc_intensity(i as f32, max as f32)),
+            Self::HslIntensity(color, min_l, max_l) => hsl_intensity(
+                color,
+                calc_hsl_intensity(i as f64, max as f64, *min_l, *max_l),
+            ),
+        }
+    }
 }
 
 pub(crate) fn rgb_intensity(color: &Rgb<bool>, i: f32) -> Rgba<u8> {
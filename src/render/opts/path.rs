@@ -11,6 +11,10 @@ pub struct PathOpts {
 }
 
 impl PathOpts {
+    pub fn build() -> PathOptsBuilder {
+        PathOptsBuilder::new()
+    }
+
     pub fn label_steps(&self) -> bool {
         self.label_steps
     }
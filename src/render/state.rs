@@ -2,6 +2,7 @@ pub(crate) mod anim;
 pub(crate) mod dist;
 pub(crate) mod graph;
 pub(crate) mod path;
+pub(crate) mod region;
 
 use super::{RenderGraph, RenderState};
 use image::Rgba;
@@ -40,6 +40,13 @@ where
         crate::render::state::dist::Builder::render_state(self)
     }
 
+    /// Render the regions owned by each start node of a [`Dist::multi`](crate::Dist::multi) distance map
+    pub fn build_regions<'r>(
+        &'r self,
+    ) -> crate::render::state::region::BuilderState<'b, 'c, 'e, 'g, 'o, 'r, G> {
+        crate::render::state::region::Builder::render_state(self)
+    }
+
     pub fn build_path<'r>(
         &'r self,
         start: usize,
@@ -163,6 +170,11 @@ where
     }
 
     fn draw_edges(&self, image: &mut RgbaImage) {
+        let color = self.opts.colors().edges();
+        for cell in self.graph.nodes() {
+            self.graph
+                .outline(cell, &self.blocks[cell.id()], color, image);
+        }
         for edge in self.edges.iter() {
             let id = edge.a().id();
             self.graph.edge(
@@ -174,6 +186,20 @@ where
                 self.opts.colors().dashed_edges(),
                 image,
             );
+
+            // split edges are drawn apart from each other so draw the other side too
+            if self.graph.split(self.graph.node(id), edge.a().side()) {
+                let id = edge.b().id();
+                self.graph.edge(
+                    self.graph.node(id),
+                    &self.blocks[id],
+                    edge.b().side(),
+                    self.opts.size().dash_width(),
+                    edge.value(),
+                    self.opts.colors().dashed_edges(),
+                    image,
+                );
+            }
         }
 
         self.edges.iter_outer().for_each(|(conn, col)| {
@@ -208,10 +208,14 @@ where
         }
     }
     pub fn default_edges<'e>(self) -> BuilderEdges<'b, 'c, 'e, 'g, 'o, G> {
-        let edges = Undirected::new(
+        let colors = self.opts.colors();
+        let edges = Undirected::new_with(
             &*self.graph,
-            *self.opts.colors().edges(),
-            *self.opts.colors().outer_edges(),
+            |g, id, n| match g.seam(g.node(id), n) {
+                true => *colors.seam_edges(),
+                false => *colors.edges(),
+            },
+            |_, _, _| *colors.outer_edges(),
         );
         BuilderEdges {
             graph: self.graph,
@@ -0,0 +1,252 @@
+use super::RenderGraph;
+use crate::algo::dist::Dist;
+use crate::graphs::{Graph, Node};
+use crate::render::opts::blend::Blend;
+use crate::render::state::graph;
+use crate::render::RenderState;
+use image::RgbaImage;
+use std::borrow::Cow;
+
+/// Renders the regions of a [`Dist::multi`] distance map, giving each region its own [`Blend`]
+/// shaded by the distance from its start.
+#[derive(Clone, Debug)]
+pub struct State<'b, 'c, 'e, 'g, 'o, 'p, 'r, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    state: Cow<'r, graph::State<'b, 'c, 'e, 'g, 'o, G>>,
+    dist: Cow<'p, Dist>,
+    blends: Vec<Blend>,
+    /// Largest distance within each region, at least 1 so regions of a single node can be shaded
+    max: Vec<usize>,
+}
+
+impl<'b, 'c, 'e, 'g, 'o, 'p, 'r, G> State<'b, 'c, 'e, 'g, 'o, 'p, 'r, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    pub fn render(&self) -> RgbaImage {
+        self.render_image()
+    }
+
+    pub fn graph(&self) -> &graph::State<'b, 'c, 'e, 'g, 'o, G> {
+        &self.state
+    }
+
+    pub fn dist(&self) -> &Dist {
+        &self.dist
+    }
+
+    pub fn blends(&self) -> &[Blend] {
+        &self.blends
+    }
+
+    /// Blend for a region; regions reuse the blends in order when there are more regions than blends
+    fn blend(&self, owner: usize) -> &Blend {
+        &self.blends[owner % self.blends.len()]
+    }
+}
+
+impl<'b, 'c, 'e, 'g, 'o, 'p, 'r, G> RenderState<'b, 'c, 'e, 'g, 'o>
+    for State<'b, 'c, 'e, 'g, 'o, 'p, 'r, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <G::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    type Graph = G;
+
+    fn render_image(&self) -> RgbaImage {
+        let mut image = crate::render::new_image(
+            &*self.state.graph,
+            self.state.opts.size(),
+            self.state.opts.colors(),
+        );
+
+        for cell in self.state.graph.nodes() {
+            self.fill(cell, &mut image);
+
+            if self.state.opts.text().show() {
+                if let Some(d) = self.dist.dist(cell.id()) {
+                    self.text(cell, &d.to_string(), &mut image);
+                }
+            }
+        }
+        self.draw_edges(&mut image);
+        image
+    }
+
+    fn fill(&self, cell: &<Self::Graph as Graph>::Node, image: &mut RgbaImage) {
+        let id = cell.id();
+        if let (Some(d), Some(owner)) = (self.dist.dist(id), self.dist.owner(id)) {
+            self.state.graph.blend_fill(
+                cell,
+                &self.state.blocks[id],
+                d,
+                self.max[owner],
+                self.blend(owner),
+                image,
+            )
+        } else if let Some(color) = &self.state.node_state[id].color {
+            self.state
+                .graph
+                .fill(cell, &self.state.blocks[id], color, image)
+        }
+    }
+
+    fn text(&self, cell: &<Self::Graph as Graph>::Node, text: &str, image: &mut RgbaImage) {
+        self.state.text(cell, text, image)
+    }
+
+    fn draw_edges(&self, image: &mut RgbaImage) {
+        self.state.draw_edges(image)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.state.size()
+    }
+}
+
+/// One hue per region, spread around the color wheel
+pub(crate) fn default_blends() -> Vec<Blend> {
+    [0.0, 120.0, 240.0, 60.0, 180.0, 300.0]
+        .into_iter()
+        .map(|h| Blend::HslIntensity(hsl::HSL { h, s: 0.8, l: 1.0 }, 0.35, 0.85))
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct Builder {}
+
+impl Builder {
+    pub fn render_state<'b, 'c, 'e, 'g, 'o, 'r, G>(
+        state: &'r graph::State<'b, 'c, 'e, 'g, 'o, G>,
+    ) -> BuilderState<'b, 'c, 'e, 'g, 'o, 'r, G>
+    where
+        G: RenderGraph + Clone + std::fmt::Debug,
+        <<G as Graph>::Node as Node>::Block: Clone + std::fmt::Debug,
+    {
+        BuilderState {
+            state: Cow::Borrowed(state),
+        }
+    }
+
+    pub fn owned_render_state<'b, 'c, 'e, 'g, 'o, 'r, G>(
+        state: graph::State<'b, 'c, 'e, 'g, 'o, G>,
+    ) -> BuilderState<'b, 'c, 'e, 'g, 'o, 'r, G>
+    where
+        G: RenderGraph + Clone + std::fmt::Debug,
+        <<G as Graph>::Node as Node>::Block: Clone + std::fmt::Debug,
+    {
+        BuilderState {
+            state: Cow::Owned(state),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BuilderState<'b, 'c, 'e, 'g, 'o, 'r, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <<G as Graph>::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    state: Cow<'r, graph::State<'b, 'c, 'e, 'g, 'o, G>>,
+}
+
+impl<'b, 'c, 'e, 'g, 'o, 'r, G> BuilderState<'b, 'c, 'e, 'g, 'o, 'r, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <<G as Graph>::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    /// Compute the regions from several start nodes, failing if any start is not in the graph
+    pub fn multi_dist<'p>(
+        self,
+        starts: &[usize],
+    ) -> Result<BuilderDist<'b, 'c, 'e, 'g, 'o, 'p, 'r, G>, crate::Error> {
+        let dist = Dist::multi(&*self.state.graph, starts)?;
+        Ok(self.owned_dist(dist))
+    }
+
+    pub fn dist<'p>(self, dist: &'p Dist) -> BuilderDist<'b, 'c, 'e, 'g, 'o, 'p, 'r, G> {
+        BuilderDist {
+            state: self.state,
+            dist: Cow::Borrowed(dist),
+        }
+    }
+
+    pub fn owned_dist<'p>(self, dist: Dist) -> BuilderDist<'b, 'c, 'e, 'g, 'o, 'p, 'r, G> {
+        BuilderDist {
+            state: self.state,
+            dist: Cow::Owned(dist),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BuilderDist<'b, 'c, 'e, 'g, 'o, 'p, 'r, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <<G as Graph>::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    state: Cow<'r, graph::State<'b, 'c, 'e, 'g, 'o, G>>,
+    dist: Cow<'p, Dist>,
+}
+
+impl<'b, 'c, 'e, 'g, 'o, 'p, 'r, G> BuilderDist<'b, 'c, 'e, 'g, 'o, 'p, 'r, G>
+where
+    G: RenderGraph + Clone + std::fmt::Debug,
+    <<G as Graph>::Node as Node>::Block: Clone + std::fmt::Debug,
+{
+    /// Use one blend per region, in the order of the start nodes.  An empty list uses the default blends.
+    pub fn blends(self, blends: Vec<Blend>) -> State<'b, 'c, 'e, 'g, 'o, 'p, 'r, G> {
+        let blends = match blends.is_empty() {
+            true => default_blends(),
+            false => blends,
+        };
+        let mut max = vec![1; self.dist.num_owners()];
+        for id in self.state.graph.node_ids() {
+            if let (Some(d), Some(owner)) = (self.dist.dist(id), self.dist.owner(id)) {
+                max[owner] = max[owner].max(d);
+            }
+        }
+        State {
+            state: self.state,
+            dist: self.dist,
+            blends,
+            max,
+        }
+    }
+
+    pub fn finish(self) -> State<'b, 'c, 'e, 'g, 'o, 'p, 'r, G> {
+        self.blends(default_blends())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphs::Graph;
+    use crate::render::opts::blend::calc_hsl_intensity;
+    use crate::{Orth, RectCell};
+
+    #[test]
+    fn regions_adjacent_starts() {
+        crate::logger(log::LevelFilter::Warn);
+        let graph: Orth<RectCell> = Orth::new_linked(2, 2);
+        let state = graph.build_render().finish();
+        // every start is next to another, so each region is only its start
+        let regions = state
+            .build_regions()
+            .multi_dist(&[0, 1, 2, 3])
+            .unwrap()
+            .finish();
+        assert_eq!(regions.max, vec![1; 4]);
+        for id in graph.node_ids() {
+            let (d, owner) = (regions.dist.dist(id), regions.dist.owner(id));
+            assert_eq!((d, owner), (Some(0), Some(id)));
+            let int = calc_hsl_intensity(0.0, regions.max[id] as f64, 0.35, 0.85);
+            assert!(int.is_finite());
+        }
+        regions.render();
+    }
+}